@@ -4,12 +4,11 @@ use std::thread::JoinHandle;
 use std::{thread, time};
 
 use tuple_store::mutex_store::MutexStore;
-use tuple_store::query_tuple::QueryTuple;
 use tuple_store::result::Result;
 use tuple_store::store::Store;
-use tuple_store::tuple::Tuple;
 use tuple_store::types::Types;
 use tuple_store::vec_store::VecStore;
+use tuple_store::{query, tuple};
 
 fn main() {
     // Create a tuple space with a VecStore tuple store.
@@ -23,7 +22,7 @@ fn main() {
     let writer_thread: JoinHandle<Result<()>> = thread::spawn(move || {
         let sleep = time::Duration::from_millis(100);
         for i in 0..100 {
-            let tuple = Tuple::builder().integer(i).integer(i).build();
+            let tuple = tuple!(i, i);
             writer_mutex_store.write(&tuple)?;
             thread::sleep(sleep);
         }
@@ -32,42 +31,40 @@ fn main() {
 
     thread::sleep(time::Duration::from_millis(200));
 
-    // Adder thread that finds and removes a tuple with two integers, and writes their sum back into the tuple
-    // space.
+    // Adder thread that waits for and removes a tuple with two integers, and writes their sum back into
+    // the tuple space.
     let adder_thread: JoinHandle<Result<()>> = thread::spawn(move || {
         // Template tuple with two Integer wild card elements.
-        let adder_query_tuple = QueryTuple::builder().any_integer().any_integer().build();
-        let sleep = time::Duration::from_millis(110);
-        while let Ok(Some(tuple)) = adder_mutex_store.take(&adder_query_tuple) {
+        let adder_query_tuple = query!(_: i64, _: i64);
+        for _ in 0..100 {
+            let tuple = adder_mutex_store.take(&adder_query_tuple)?;
             if let (Types::Integer(num_1), Types::Integer(num_2)) = (&tuple[0], &tuple[1]) {
-                let sum_tuple = Tuple::builder().integer(num_1 + num_2).build();
+                let sum_tuple = tuple!(num_1 + num_2);
                 adder_mutex_store.write(&sum_tuple)?;
             }
-            thread::sleep(sleep);
         }
         Ok(())
     });
 
-    // Printer thread that removes single elemen integer tuples and prints them to stdout.
+    // Printer thread that waits for and removes single element integer tuples and prints them to stdout.
     let printer_thread: JoinHandle<Result<()>> = thread::spawn(move || {
-        let printer_query_tuple = QueryTuple::builder().any_integer().build();
-        let sleep = time::Duration::from_millis(120);
-        while let Ok(Some(tuple)) = print_mutex_store.take(&printer_query_tuple) {
+        let printer_query_tuple = query!(_: i64);
+        for _ in 0..100 {
+            let tuple = print_mutex_store.take(&printer_query_tuple)?;
             if let Types::Integer(num) = &tuple[0] {
                 println!("Printer: {}", num);
             }
-            thread::sleep(sleep);
         }
         Ok(())
     });
 
-    if let Err(_) = writer_thread.join() {
+    if writer_thread.join().is_err() {
         panic!("Writer panic")
     };
-    if let Err(_) = adder_thread.join() {
+    if adder_thread.join().is_err() {
         panic!("Adder panic")
     };
-    if let Err(_) = printer_thread.join() {
+    if printer_thread.join().is_err() {
         panic!("Printer panic")
     };
 }
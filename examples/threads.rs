@@ -39,7 +39,7 @@ fn main() {
         let query_tuple = QueryTuple::builder().any().any().build();
         let reader_sleep = time::Duration::from_millis(500);
 
-        while let Some(tuple) = reader_mutex_store.read(&query_tuple)? {
+        while let Some(tuple) = reader_mutex_store.read_now(&query_tuple)? {
             println!("Reader: Read: {:?}", tuple);
             num_tuples += 1;
             thread::sleep(reader_sleep);
@@ -56,7 +56,7 @@ fn main() {
         let query_tuple = QueryTuple::builder().any_integer().any_integer().build();
         let taker_sleep = time::Duration::from_millis(110);
 
-        while let Some(tuple) = taker_mutex_store.take(&query_tuple)? {
+        while let Some(tuple) = taker_mutex_store.take_now(&query_tuple)? {
             println!("Taker: Took: {:?}", tuple);
             num_tuples += 1;
             thread::sleep(taker_sleep);
@@ -82,16 +82,16 @@ fn main() {
         Ok(())
     });
 
-    if let Err(_) = writer_1_thread.join() {
+    if writer_1_thread.join().is_err() {
         panic!("Writer 1 panic")
     };
-    if let Err(_) = writer_2_thread.join() {
+    if writer_2_thread.join().is_err() {
         panic!("Writer 2 panic")
     };
-    if let Err(_) = taker_thread.join() {
+    if taker_thread.join().is_err() {
         panic!("Taker panic")
     };
-    if let Err(_) = reader_thread.join() {
+    if reader_thread.join().is_err() {
         panic!("Reader panic")
     };
 
@@ -0,0 +1,246 @@
+use crate::async_store::AsyncStore;
+use crate::mutex_store::MutexStore;
+use crate::query_tuple::QueryTuple;
+use crate::result::Result;
+use crate::store::Store;
+use crate::tuple::Tuple;
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An [AsyncMutexStore] is an [AsyncStore] that shares its storage with a [MutexStore].
+///
+/// Pending reads and takes register the waker of their task against their query tuple, and are
+/// woken when a tuple satisfying it is written, either through the [AsyncMutexStore] or through
+/// any [MutexStore] clone it was created from. Only [std::task] is used, so the futures can be
+/// driven by any executor.
+/// ```rust
+/// use tuple_store::async_mutex_store::AsyncMutexStore;
+/// use tuple_store::async_store::AsyncStore;
+/// use tuple_store::query_tuple::QueryTuple;
+/// use tuple_store::tuple::Tuple;
+/// use tuple_store::vec_store::VecStore;
+///
+/// async fn double(store: &mut AsyncMutexStore<VecStore>) -> tuple_store::result::Result<()> {
+///   let query_tuple = QueryTuple::builder().string("double").any_integer().build();
+///   let tuple = store.take(&query_tuple).await?;
+///   if let tuple_store::types::Types::Integer(integer) = tuple[1] {
+///     store.write(&Tuple::builder().string("doubled").integer(integer * 2).build()).await?;
+///   }
+///   Ok(())
+/// }
+/// ```
+pub struct AsyncMutexStore<S: Store> {
+    store: MutexStore<S>,
+}
+
+impl<S: Store> AsyncMutexStore<S> {
+    /// A [MutexStore] sharing storage with this store, for use by threads outside the executor.
+    pub fn mutex_store(&self) -> MutexStore<S> {
+        self.store.clone()
+    }
+}
+
+impl<S: Store> Clone for AsyncMutexStore<S> {
+    fn clone(&self) -> AsyncMutexStore<S> {
+        AsyncMutexStore {
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl<S: Store> Default for AsyncMutexStore<S> {
+    fn default() -> AsyncMutexStore<S> {
+        AsyncMutexStore {
+            store: MutexStore::default(),
+        }
+    }
+}
+
+impl<S: Store> From<MutexStore<S>> for AsyncMutexStore<S> {
+    fn from(store: MutexStore<S>) -> AsyncMutexStore<S> {
+        AsyncMutexStore { store }
+    }
+}
+
+impl<S: Store> AsyncStore for AsyncMutexStore<S> {
+    fn size(&self) -> impl Future<Output = Result<usize>> + '_ {
+        future::ready(self.store.size())
+    }
+
+    fn write<'a>(&'a mut self, tuple: &'a Tuple) -> impl Future<Output = Result<()>> + 'a {
+        future::ready(self.store.write(tuple))
+    }
+
+    fn read<'a>(&'a self, query_tuple: &'a QueryTuple) -> impl Future<Output = Result<Tuple>> + 'a {
+        Read {
+            store: &self.store,
+            query_tuple,
+            waiter: None,
+        }
+    }
+
+    fn take<'a>(
+        &'a mut self,
+        query_tuple: &'a QueryTuple,
+    ) -> impl Future<Output = Result<Tuple>> + 'a {
+        Take {
+            store: &mut self.store,
+            query_tuple,
+            waiter: None,
+        }
+    }
+}
+
+/// Future returned by [AsyncMutexStore::read].
+struct Read<'a, S: Store> {
+    store: &'a MutexStore<S>,
+    query_tuple: &'a QueryTuple,
+    waiter: Option<usize>,
+}
+
+impl<S: Store> Future for Read<'_, S> {
+    type Output = Result<Tuple>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        let Read {
+            store,
+            query_tuple,
+            waiter,
+        } = self.get_mut();
+        store.poll_for(query_tuple, waiter, context, |store| {
+            store.read_now(query_tuple)
+        })
+    }
+}
+
+impl<S: Store> Drop for Read<'_, S> {
+    fn drop(&mut self) {
+        if let Some(id) = self.waiter {
+            self.store.forget_waiter(id);
+        }
+    }
+}
+
+/// Future returned by [AsyncMutexStore::take].
+struct Take<'a, S: Store> {
+    store: &'a mut MutexStore<S>,
+    query_tuple: &'a QueryTuple,
+    waiter: Option<usize>,
+}
+
+impl<S: Store> Future for Take<'_, S> {
+    type Output = Result<Tuple>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        let Take {
+            store,
+            query_tuple,
+            waiter,
+        } = self.get_mut();
+        store.poll_for(query_tuple, waiter, context, |store| {
+            store.take_now(query_tuple)
+        })
+    }
+}
+
+impl<S: Store> Drop for Take<'_, S> {
+    fn drop(&mut self) {
+        if let Some(id) = self.waiter {
+            self.store.forget_waiter(id);
+        }
+    }
+}
+
+#[test]
+fn test_async_mutex_store() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+    use std::thread::{self, Thread};
+    use std::time::Duration;
+
+    /// Wakes the thread running `block_on`.
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// A minimal executor that parks the current thread until the future is woken.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    let mut store = AsyncMutexStore::<VecStore>::default();
+    let mut mutex_store = store.mutex_store();
+
+    let writer_thread = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        mutex_store.write(&Tuple::builder().integer(1).build())?;
+        mutex_store.write(&Tuple::builder().integer(2).build())
+    });
+
+    let query_tuple = QueryTuple::builder().integer(2).build();
+    let tuple = block_on(store.take(&query_tuple))?;
+    assert_eq!(query_tuple, tuple);
+    writer_thread.join().expect("Writer panic")?;
+
+    block_on(store.write(&Tuple::builder().string("S").build()))?;
+    let query_tuple = QueryTuple::builder().any_string().build();
+    let tuple = block_on(store.read(&query_tuple))?;
+    assert_eq!(query_tuple, tuple);
+    assert_eq!(2, block_on(store.size())?);
+
+    Ok(())
+}
+
+#[test]
+fn test_async_mutex_store_wakeups() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let store = AsyncMutexStore::<VecStore>::default();
+    let mut mutex_store = store.mutex_store();
+    let counting_waker = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counting_waker.clone());
+    let mut context = Context::from_waker(&waker);
+
+    let query_tuple = QueryTuple::builder().integer(2).build();
+    let mut read = std::pin::pin!(store.read(&query_tuple));
+    assert!(read.as_mut().poll(&mut context).is_pending());
+
+    mutex_store.write(&Tuple::builder().integer(1).build())?;
+    assert_eq!(0, counting_waker.0.load(Ordering::SeqCst));
+
+    mutex_store.write(&Tuple::builder().integer(2).build())?;
+    assert_eq!(1, counting_waker.0.load(Ordering::SeqCst));
+
+    match read.as_mut().poll(&mut context) {
+        Poll::Ready(tuple) => assert_eq!(query_tuple, tuple?),
+        Poll::Pending => panic!("No tuple found"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::query_tuple::QueryTuple;
+use crate::result::Result;
+use crate::tuple::Tuple;
+use std::future::Future;
+
+/// The asynchronous counterpart of [Store](crate::store::Store). Reads and takes are futures
+/// that resolve once a matching tuple is available, so they never block the executor.
+pub trait AsyncStore: Default {
+    /// The number of tuples in the store.
+    fn size(&self) -> impl Future<Output = Result<usize>> + '_;
+
+    /// Writes a tuple into the store, waking the pending reads and takes it satisfies.
+    fn write<'a>(&'a mut self, tuple: &'a Tuple) -> impl Future<Output = Result<()>> + 'a;
+
+    /// Reads a tuple from the store, matching the query tuple. Does not remove the tuple from
+    /// the store. Resolves once a matching tuple is available (Linda `rd`).
+    fn read<'a>(&'a self, query_tuple: &'a QueryTuple) -> impl Future<Output = Result<Tuple>> + 'a;
+
+    /// Reads a tuple from the store, matching the query tuple. Removes the tuple from the
+    /// store. Resolves once a matching tuple is available (Linda `in`).
+    fn take<'a>(
+        &'a mut self,
+        query_tuple: &'a QueryTuple,
+    ) -> impl Future<Output = Result<Tuple>> + 'a;
+}
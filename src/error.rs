@@ -1,4 +1,4 @@
-use crate::store::Store;
+use crate::timestamp::Timestamp;
 use crate::tuple::Tuple;
 use serde::{Deserialize, Serialize};
 
@@ -7,13 +7,19 @@ use serde::{Deserialize, Serialize};
 pub enum Error {
     MutexPoisonError,
     NonConcreteTuple(Tuple),
+    /// No matching tuple became available before the deadline of a blocking operation.
+    Timeout,
+    /// A regular expression could not be compiled, with the reason.
+    InvalidRegex(String),
+    /// Text could not be parsed, with the reason.
+    InvalidSyntax(String),
+    /// A timestamp lies outside the range of the platform's
+    /// [SystemTime](std::time::SystemTime).
+    TimestampOutOfRange(Timestamp),
 }
 
-impl<S> From<std::sync::PoisonError<std::sync::MutexGuard<'_, S>>> for Error
-where
-    S: Store,
-{
-    fn from(_error: std::sync::PoisonError<std::sync::MutexGuard<'_, S>>) -> Error {
+impl<T> From<std::sync::PoisonError<T>> for Error {
+    fn from(_error: std::sync::PoisonError<T>) -> Error {
         Error::MutexPoisonError
     }
 }
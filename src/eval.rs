@@ -0,0 +1,206 @@
+use crate::mutex_store::MutexStore;
+use crate::query_tuple::QueryTuple;
+use crate::store::Store;
+use crate::tuple::Tuple;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// The first element of the tuples written by an [EvalPool] when a computation fails.
+pub const ERROR_TUPLE_TAG: &str = "eval_error";
+/// The second element of an error tuple when the computation returned an error.
+pub const ERROR_KIND_ERROR: &str = "error";
+/// The second element of an error tuple when the computation panicked.
+pub const ERROR_KIND_PANIC: &str = "panic";
+
+type Job = Box<dyn FnOnce() -> std::result::Result<Tuple, String> + Send>;
+
+/// An [EvalPool] runs computations on a pool of worker threads and writes the resulting tuples
+/// into a [MutexStore] (Linda `eval`).
+///
+/// A computation that returns an error or panics writes an error tuple
+/// `(ERROR_TUPLE_TAG, kind, message)` instead, where `kind` is [ERROR_KIND_ERROR] or
+/// [ERROR_KIND_PANIC]. [EvalPool::error_query_tuple] matches these tuples.
+/// ```rust
+/// use tuple_store::eval::EvalPool;
+/// use tuple_store::mutex_store::MutexStore;
+/// use tuple_store::query_tuple::QueryTuple;
+/// use tuple_store::tuple::Tuple;
+/// use tuple_store::vec_store::VecStore;
+///
+/// fn main() -> tuple_store::result::Result<()> {
+///   let mut store = MutexStore::<VecStore>::default();
+///   let eval_pool = EvalPool::builder().workers(2).build(&store);
+///
+///   for i in 0..10 {
+///     eval_pool.eval(move || Ok::<_, String>(Tuple::builder().integer(i).integer(i * i).build()));
+///   }
+///
+///   let query_tuple = QueryTuple::builder().any_integer().any_integer().build();
+///   for _ in 0..10 {
+///     println!("Square: {}", store.take(&query_tuple)?);
+///   }
+///   Ok(())
+/// }
+/// ```
+pub struct EvalPool {
+    sender: Option<mpsc::Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl EvalPool {
+    /// Returns an [EvalPoolBuilder] so that the number of workers can be modified.
+    pub fn builder() -> EvalPoolBuilder {
+        EvalPoolBuilder::default()
+    }
+
+    /// Queues `computation` to be run by a worker. The tuple it returns is written into the
+    /// store once it finishes.
+    pub fn eval<F, E>(&self, computation: F)
+    where
+        F: FnOnce() -> std::result::Result<Tuple, E> + Send + 'static,
+        E: std::fmt::Debug,
+    {
+        let job: Job = Box::new(move || computation().map_err(|error| format!("{:?}", error)));
+        if let Some(Err(error)) = self.sender.as_ref().map(|sender| sender.send(job)) {
+            log::error!("EvalPool has no workers left: {}", error);
+        }
+    }
+
+    /// A query tuple matching the error tuples written by an [EvalPool].
+    pub fn error_query_tuple() -> QueryTuple {
+        QueryTuple::builder()
+            .string(ERROR_TUPLE_TAG)
+            .any_string()
+            .any_string()
+            .build()
+    }
+
+    /// Waits for all queued computations to finish and stops the workers.
+    pub fn join(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            if worker.join().is_err() {
+                log::error!("EvalPool worker panicked");
+            }
+        }
+    }
+
+    fn work<S: Store + Send + 'static>(
+        mut store: MutexStore<S>,
+        receiver: Arc<Mutex<mpsc::Receiver<Job>>>,
+    ) {
+        loop {
+            let job = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => return,
+            };
+            let job = match job {
+                Ok(job) => job,
+                Err(_) => return,
+            };
+            let tuple = match panic::catch_unwind(AssertUnwindSafe(job)) {
+                Ok(Ok(tuple)) => tuple,
+                Ok(Err(message)) => error_tuple(ERROR_KIND_ERROR, &message),
+                Err(payload) => error_tuple(ERROR_KIND_PANIC, &panic_message(payload)),
+            };
+            if let Err(error) = store.write(&tuple) {
+                log::error!("EvalPool could not write {}: {:?}", tuple, error);
+            }
+        }
+    }
+}
+
+impl Drop for EvalPool {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn error_tuple(kind: &str, message: &str) -> Tuple {
+    Tuple::builder()
+        .string(ERROR_TUPLE_TAG)
+        .string(kind)
+        .string(message)
+        .build()
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => String::from(*message),
+            Err(_) => String::from("unknown panic"),
+        },
+    }
+}
+
+pub struct EvalPoolBuilder {
+    workers: usize,
+}
+
+impl EvalPoolBuilder {
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Starts the workers, which write their results into `store`.
+    pub fn build<S: Store + Send + 'static>(self, store: &MutexStore<S>) -> EvalPool {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..self.workers.max(1))
+            .map(|_| {
+                let store = store.clone();
+                let receiver = receiver.clone();
+                thread::spawn(move || EvalPool::work(store, receiver))
+            })
+            .collect();
+        EvalPool {
+            sender: Some(sender),
+            workers,
+        }
+    }
+}
+
+impl Default for EvalPoolBuilder {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, |workers| workers.get()),
+        }
+    }
+}
+
+#[test]
+fn test_eval_pool() -> crate::result::Result<()> {
+    use crate::types::Types;
+    use crate::vec_store::VecStore;
+
+    let mut store = MutexStore::<VecStore>::default();
+    let eval_pool = EvalPool::builder().workers(2).build(&store);
+
+    eval_pool.eval(|| Ok::<_, String>(Tuple::builder().integer(42).build()));
+    eval_pool.eval(|| Err::<Tuple, _>("failed"));
+    eval_pool.eval(|| -> std::result::Result<Tuple, String> { panic!("panicked") });
+    eval_pool.join();
+
+    assert_eq!(3, store.size()?);
+    store.take(&QueryTuple::builder().integer(42).build())?;
+
+    let mut kinds = Vec::new();
+    while let Some(tuple) = store.take_now(&EvalPool::error_query_tuple())? {
+        if let (Types::String(kind), Types::String(message)) = (&tuple[1], &tuple[2]) {
+            kinds.push(kind.clone());
+            assert!(message.contains("failed") || message.contains("panicked"));
+        }
+    }
+    kinds.sort();
+    assert_eq!(vec![ERROR_KIND_ERROR, ERROR_KIND_PANIC], kinds);
+
+    Ok(())
+}
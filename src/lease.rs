@@ -0,0 +1,96 @@
+use crate::mutex_store::MutexStore;
+use crate::store::Store;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Handle to a tuple written with [Store::write_with_lease], used to renew the lease with
+/// [Store::renew_lease] or cancel it with [Store::cancel_lease].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LeaseId(u64);
+
+impl LeaseId {
+    pub(crate) fn new(id: u64) -> LeaseId {
+        LeaseId(id)
+    }
+}
+
+/// A [Reaper] is a background thread that periodically removes tuples with an expired lease from
+/// a [MutexStore]. The thread is stopped when the [Reaper] is dropped. Wrapping a
+/// [SubscriptionStore](crate::subscription_store::SubscriptionStore) notifies its subscribers of
+/// each removal with [Event::Expired](crate::subscription_store::Event::Expired).
+/// ```rust
+/// use std::time::Duration;
+/// use tuple_store::lease::Reaper;
+/// use tuple_store::mutex_store::MutexStore;
+/// use tuple_store::store::Store;
+/// use tuple_store::tuple::Tuple;
+/// use tuple_store::vec_store::VecStore;
+///
+/// fn main() -> tuple_store::result::Result<()> {
+///   let mut store = MutexStore::<VecStore>::default();
+///   let _reaper = Reaper::spawn(&store, Duration::from_secs(1));
+///
+///   let heartbeat = Tuple::builder().string("heartbeat").integer(1).build();
+///   let lease_id = store.write_with_lease(&heartbeat, Duration::from_secs(5))?;
+///   store.renew_lease(lease_id, Duration::from_secs(5))?;
+///   Ok(())
+/// }
+/// ```
+pub struct Reaper {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Reaper {
+    /// Starts a thread calling [Store::purge_expired] on `store` every `interval`.
+    pub fn spawn<S: Store + Send + 'static>(store: &MutexStore<S>, interval: Duration) -> Reaper {
+        let mut store = store.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                if let Err(error) = store.purge_expired() {
+                    log::error!("Reaper could not purge expired tuples: {:?}", error);
+                }
+            }
+        });
+        Reaper {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Reaper {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("Reaper thread panicked");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_reaper() -> crate::result::Result<()> {
+    use crate::tuple::Tuple;
+    use crate::vec_store::VecStore;
+
+    let mut store = MutexStore::<VecStore>::default();
+    let reaper = Reaper::spawn(&store, Duration::from_millis(10));
+
+    store.write_with_lease(
+        &Tuple::builder().string("heartbeat").build(),
+        Duration::from_millis(10),
+    )?;
+    store.write(&Tuple::builder().string("result").build())?;
+    thread::sleep(Duration::from_millis(50));
+    drop(reaper);
+
+    assert_eq!(1, store.size()?);
+    assert!(store.purge_expired()?.is_empty());
+
+    Ok(())
+}
@@ -1,14 +1,35 @@
 //! A simple Tuple Space implementation for Rust.
 
+/// Asynchronous wrapper around a MutexStore
+pub mod async_mutex_store;
+/// Trait required to be an asynchronous Tuple store
+pub mod async_store;
 /// Tuple Space specific Error
 pub mod error;
+/// Worker pool computing tuples (Linda `eval`)
+pub mod eval;
+/// Expiring tuples
+pub mod lease;
+/// The `tuple!` and `query!` macros
+#[doc(hidden)]
+pub mod macros;
 /// Thread safe Tuple Space store wrapper
 pub mod mutex_store;
+/// Text syntax for tuples and query tuples
+pub mod parse;
+/// Compiled regular expressions for matching strings
+pub mod pattern;
+/// User defined matchers for elements and tuples
+pub mod predicate;
 pub mod query_tuple;
 /// Tuple Space specific Result
 pub mod result;
 /// Trait required to be a Tuple store
 pub mod store;
+/// Tuple store wrapper notifying subscribers of changes
+pub mod subscription_store;
+/// UTC timestamps that can be stored in a Tuple
+pub mod timestamp;
 /// Storage unit for the Tuple Space
 pub mod tuple;
 /// Types that can be stored in a Tuple
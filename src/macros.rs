@@ -0,0 +1,165 @@
+use crate::query_tuple::QueryTupleBuilder;
+use crate::tuple::TupleBuilder;
+
+/// Builds a [Tuple](crate::tuple::Tuple) from a list of values:
+/// * an integer, float, string or boolean literal or expression is added with
+///   [TupleBuilder::integer], [TupleBuilder::float], [TupleBuilder::string] or
+///   [TupleBuilder::boolean]
+/// * `(...)` adds a nested tuple with the values within the parentheses
+/// ```rust
+/// use tuple_store::tuple;
+/// use tuple_store::tuple::Tuple;
+///
+/// let tuple = tuple!(1, 2.0, "s", true);
+/// assert_eq!(tuple, Tuple::builder().integer(1).float(2.0).string("s").boolean(true).build());
+/// ```
+#[macro_export]
+macro_rules! tuple {
+    (@munch $builder:expr;) => {
+        $builder.build()
+    };
+    (@munch $builder:expr; ($($nested:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::tuple!(@munch $builder.tuple(&$crate::tuple!($($nested)*)); $($($rest)*)?)
+    };
+    (@munch $builder:expr; $element:expr $(, $($rest:tt)*)?) => {
+        $crate::tuple!(@munch $crate::macros::Literal::add_to_tuple($element, $builder); $($($rest)*)?)
+    };
+    ($($elements:tt)*) => {
+        $crate::tuple!(@munch $crate::tuple::Tuple::builder(); $($elements)*)
+    };
+}
+
+/// Builds a [QueryTuple](crate::query_tuple::QueryTuple) from a list of matchers:
+/// * `_` matches any element, with [QueryTupleBuilder::any]
+/// * `_: i64`, `_: f64`, `_: &str` or `String`, and `_: bool` match any element of that type,
+///   with [QueryTupleBuilder::any_integer], [QueryTupleBuilder::any_float],
+///   [QueryTupleBuilder::any_string] or [QueryTupleBuilder::any_boolean]
+/// * `..`, only as the last matcher, matches any further elements, with [QueryTupleBuilder::rest]
+/// * `(...)` matches a nested tuple with the matchers within the parentheses
+/// * an integer, float, string or boolean literal or expression matches its value exactly, with
+///   [QueryTupleBuilder::integer], [QueryTupleBuilder::float], [QueryTupleBuilder::string] or
+///   [QueryTupleBuilder::boolean]
+/// ```rust
+/// use tuple_store::query_tuple::QueryTuple;
+/// use tuple_store::query;
+///
+/// let query_tuple = query!(_: i64, 5, "s", _);
+/// assert_eq!(4, query_tuple.len());
+/// let query_tuple = query!("event", ..);
+/// assert!(query_tuple.rest().is_some());
+/// ```
+#[macro_export]
+macro_rules! query {
+    (@munch $builder:expr;) => {
+        $builder.build()
+    };
+    (@munch $builder:expr; ..) => {
+        $builder.rest().build()
+    };
+    (@munch $builder:expr; _ : i64 $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $builder.any_integer(); $($($rest)*)?)
+    };
+    (@munch $builder:expr; _ : f64 $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $builder.any_float(); $($($rest)*)?)
+    };
+    (@munch $builder:expr; _ : &str $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $builder.any_string(); $($($rest)*)?)
+    };
+    (@munch $builder:expr; _ : String $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $builder.any_string(); $($($rest)*)?)
+    };
+    (@munch $builder:expr; _ : bool $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $builder.any_boolean(); $($($rest)*)?)
+    };
+    (@munch $builder:expr; _ $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $builder.any(); $($($rest)*)?)
+    };
+    (@munch $builder:expr; ($($nested:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $builder.tuple($crate::query!($($nested)*)); $($($rest)*)?)
+    };
+    (@munch $builder:expr; $element:expr $(, $($rest:tt)*)?) => {
+        $crate::query!(@munch $crate::macros::Literal::add_to_query($element, $builder); $($($rest)*)?)
+    };
+    ($($matchers:tt)*) => {
+        $crate::query!(@munch $crate::query_tuple::QueryTuple::builder(); $($matchers)*)
+    };
+}
+
+/// The kinds of Rust literals the [tuple!](crate::tuple!) and [query!](crate::query!) macros
+/// accept, each added with the builder method for its kind. Only [i64] is implemented for
+/// integers, so integer literals are inferred as [i64].
+#[doc(hidden)]
+pub trait Literal {
+    fn add_to_tuple(self, builder: TupleBuilder) -> TupleBuilder;
+    fn add_to_query(self, builder: QueryTupleBuilder) -> QueryTupleBuilder;
+}
+
+macro_rules! literals {
+    ($(($type:ty, $method:ident)),+) => {
+        $(
+            impl Literal for $type {
+                fn add_to_tuple(self, builder: TupleBuilder) -> TupleBuilder {
+                    builder.$method(self)
+                }
+
+                fn add_to_query(self, builder: QueryTupleBuilder) -> QueryTupleBuilder {
+                    builder.$method(self)
+                }
+            }
+        )*
+    };
+}
+
+literals!(
+    (i64, integer),
+    (f64, float),
+    (&str, string),
+    (bool, boolean)
+);
+
+#[test]
+fn test_tuple_macro() {
+    use crate::tuple::Tuple;
+
+    assert!(tuple!().is_empty());
+    assert_eq!(
+        Tuple::builder()
+            .integer(1)
+            .float(2.0)
+            .string("s")
+            .boolean(true)
+            .build(),
+        tuple!(1, 2.0, "s", true)
+    );
+
+    let (integer, owned) = (5, String::from("owned"));
+    let tuple = tuple!(
+        -integer,
+        integer + 1,
+        owned.as_str(),
+        ("nested", 1.5),
+        false,
+    );
+    let expected = Tuple::builder()
+        .integer(-5)
+        .integer(6)
+        .string("owned")
+        .tuple(&Tuple::builder().string("nested").float(1.5).build())
+        .boolean(false)
+        .build();
+    assert_eq!(expected, tuple);
+}
+
+#[test]
+fn test_query_macro() {
+    let tuple = tuple!(1, 5, "s", true);
+    assert_eq!(query!(_: i64, 5, "s", _), tuple);
+    assert_eq!(query!(_: i64, _: i64, _: String, _: bool,), tuple);
+    assert_eq!(query!(1, ..), tuple);
+    assert_ne!(query!(_: f64, 5, "s", _), tuple);
+    assert_ne!(query!(_, _, _), tuple);
+    assert_ne!(query!(_, _, _: &str, false), tuple);
+    assert_eq!(query!((_, _: &str), ..), tuple!((1, "a")));
+    assert_eq!(query!(_: f64), tuple!(-2.5));
+    assert!(query!().is_empty());
+}
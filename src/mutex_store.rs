@@ -1,14 +1,23 @@
-use crate::query_tuple::QueryTuple;
+use crate::error::Error;
+use crate::lease::LeaseId;
+use crate::query_tuple::{Bindings, QueryTuple};
 use crate::result::Result;
 use crate::store::Store;
 use crate::tuple::Tuple;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 
 /// A [MutexStore] is a thread safe Tuple store wrapper that uses another tuple store as internal
 /// storage.
 ///
 /// The internal tuple store is encased in an [Arc]<[Mutex]<T>> making the [MutexStore] thread
 /// safe across clones.
+///
+/// Besides the non-blocking [Store::read_now] and [Store::take_now], a [MutexStore] offers the
+/// blocking [MutexStore::read] and [MutexStore::take] (Linda `rd` and `in`). These wait until a
+/// matching tuple is written by another clone of the store. Their `_timeout` and `_until`
+/// variants give up with [Error::Timeout] once a duration or deadline has passed.
 /// ```rust
 /// use tuple_store::mutex_store::MutexStore;
 /// use tuple_store::tuple::Tuple;
@@ -27,36 +36,359 @@ use std::sync::{Arc, Mutex};
 ///   Ok(())
 /// }
 /// ```
-#[derive(Clone)]
 pub struct MutexStore<S: Store> {
-    store: Arc<Mutex<S>>,
+    space: Arc<Mutex<Space<S>>>,
 }
 
-impl<S: Store> MutexStore<S> {}
+/// The state shared between clones of a [MutexStore]: the internal store and the threads
+/// waiting for a tuple to be written to it.
+struct Space<S: Store> {
+    store: S,
+    waiters: Waiters,
+}
 
-impl<S: Store> Default for MutexStore<S> {
-    fn default() -> MutexStore<S> {
+/// How a [Waiter] is woken once a tuple satisfying its query tuple is written.
+enum Wakeup {
+    /// A thread blocked in [MutexStore::read] or [MutexStore::take].
+    Thread(Arc<Condvar>),
+    /// A pending future of an [AsyncMutexStore](crate::async_mutex_store::AsyncMutexStore).
+    Task(Waker),
+}
+
+struct Waiter {
+    id: usize,
+    query_tuples: Vec<QueryTuple>,
+    wakeup: Wakeup,
+}
+
+#[derive(Default)]
+struct Waiters {
+    waiters: Vec<Waiter>,
+    next_id: usize,
+}
+
+impl Waiters {
+    fn register(&mut self, query_tuples: &[QueryTuple], wakeup: Wakeup) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.waiters.push(Waiter {
+            id,
+            query_tuples: query_tuples.to_vec(),
+            wakeup,
+        });
+        id
+    }
+
+    fn update(&mut self, id: usize, wakeup: Wakeup) {
+        if let Some(waiter) = self.waiters.iter_mut().find(|waiter| waiter.id == id) {
+            waiter.wakeup = wakeup;
+        }
+    }
+
+    fn deregister(&mut self, id: usize) {
+        self.waiters.retain(|waiter| waiter.id != id);
+    }
+
+    /// Wakes the waiters with a query tuple that is satisfied by `tuple`.
+    fn notify(&self, tuple: &Tuple) {
+        self.waiters
+            .iter()
+            .filter(|waiter| {
+                waiter
+                    .query_tuples
+                    .iter()
+                    .any(|query_tuple| query_tuple == tuple)
+            })
+            .for_each(|waiter| match &waiter.wakeup {
+                Wakeup::Thread(condvar) => condvar.notify_one(),
+                Wakeup::Task(waker) => waker.wake_by_ref(),
+            });
+    }
+}
+
+/// Deregisters the waiter of a blocking call when dropped, whether the call returned a tuple, an
+/// error or panicked. The lock must not be held when it is dropped.
+struct Registration<'a, S: Store> {
+    space: &'a Mutex<Space<S>>,
+    id: usize,
+}
+
+impl<S: Store> Drop for Registration<'_, S> {
+    fn drop(&mut self) {
+        self.space
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .waiters
+            .deregister(self.id);
+    }
+}
+
+impl<S: Store> MutexStore<S> {
+    /// Wraps `store`, which may already contain tuples.
+    pub fn new(store: S) -> MutexStore<S> {
         MutexStore {
-            store: Arc::new(Mutex::new(S::default())),
+            space: Arc::new(Mutex::new(Space {
+                store,
+                waiters: Waiters::default(),
+            })),
         }
     }
+
+    /// Reads a tuple matching the query tuple without removing it from the store, blocking
+    /// until one is available (Linda `rd`).
+    pub fn read(&self, query_tuple: &QueryTuple) -> Result<Tuple> {
+        self.wait_for(std::slice::from_ref(query_tuple), None, |store| {
+            store.read_now(query_tuple)
+        })
+    }
+
+    /// Like [MutexStore::read], but returns [Error::Timeout] if no matching tuple is written
+    /// within `timeout`. A `timeout` too long to represent as an [Instant] never expires.
+    pub fn read_timeout(&self, query_tuple: &QueryTuple, timeout: Duration) -> Result<Tuple> {
+        let deadline = Instant::now().checked_add(timeout);
+        self.wait_for(std::slice::from_ref(query_tuple), deadline, |store| {
+            store.read_now(query_tuple)
+        })
+    }
+
+    /// Like [MutexStore::read], but returns [Error::Timeout] if no matching tuple is written
+    /// before `deadline`.
+    pub fn read_until(&self, query_tuple: &QueryTuple, deadline: Instant) -> Result<Tuple> {
+        self.wait_for(std::slice::from_ref(query_tuple), Some(deadline), |store| {
+            store.read_now(query_tuple)
+        })
+    }
+
+    /// Removes a tuple matching the query tuple from the store, blocking until one is
+    /// available (Linda `in`).
+    pub fn take(&mut self, query_tuple: &QueryTuple) -> Result<Tuple> {
+        self.wait_for(std::slice::from_ref(query_tuple), None, |store| {
+            store.take_now(query_tuple)
+        })
+    }
+
+    /// Like [MutexStore::take], but returns [Error::Timeout] if no matching tuple is written
+    /// within `timeout`. A `timeout` too long to represent as an [Instant] never expires.
+    pub fn take_timeout(&mut self, query_tuple: &QueryTuple, timeout: Duration) -> Result<Tuple> {
+        let deadline = Instant::now().checked_add(timeout);
+        self.wait_for(std::slice::from_ref(query_tuple), deadline, |store| {
+            store.take_now(query_tuple)
+        })
+    }
+
+    /// Like [MutexStore::take], but returns [Error::Timeout] if no matching tuple is written
+    /// before `deadline`.
+    pub fn take_until(&mut self, query_tuple: &QueryTuple, deadline: Instant) -> Result<Tuple> {
+        self.wait_for(std::slice::from_ref(query_tuple), Some(deadline), |store| {
+            store.take_now(query_tuple)
+        })
+    }
+
+    /// Like [MutexStore::read], but also returns the values bound to the variables of the query
+    /// tuple.
+    pub fn read_bound(&self, query_tuple: &QueryTuple) -> Result<(Tuple, Bindings)> {
+        self.wait_for(std::slice::from_ref(query_tuple), None, |store| {
+            store.read_bound_now(query_tuple)
+        })
+    }
+
+    /// Like [MutexStore::take], but also returns the values bound to the variables of the query
+    /// tuple.
+    pub fn take_bound(&mut self, query_tuple: &QueryTuple) -> Result<(Tuple, Bindings)> {
+        self.wait_for(std::slice::from_ref(query_tuple), None, |store| {
+            store.take_bound_now(query_tuple)
+        })
+    }
+
+    /// Removes one distinct tuple for each query tuple from the store, blocking until all of
+    /// them are available. No tuple is removed until all of them can be.
+    pub fn take_many(&mut self, query_tuples: &[QueryTuple]) -> Result<Vec<Tuple>> {
+        self.wait_for(query_tuples, None, |store| {
+            store.take_many_now(query_tuples)
+        })
+    }
+
+    /// Calls `operation` on the internal store until it returns a result, sleeping between
+    /// attempts until a tuple satisfying one of `query_tuples` is written or `deadline` passes.
+    fn wait_for<T, F>(
+        &self,
+        query_tuples: &[QueryTuple],
+        deadline: Option<Instant>,
+        mut operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut(&mut S) -> Result<Option<T>>,
+    {
+        let mut space = self.lock()?;
+        if let Some(found) = operation(&mut space.store)? {
+            return Ok(found);
+        }
+
+        let condvar = Arc::new(Condvar::new());
+        let id = space
+            .waiters
+            .register(query_tuples, Wakeup::Thread(condvar.clone()));
+        let _registration = Registration {
+            space: &self.space,
+            id,
+        };
+        Self::wait_registered(space, &condvar, deadline, operation)
+    }
+
+    /// The waiting loop of [MutexStore::wait_for]. Takes ownership of `space` so that the lock
+    /// is released on every exit path before the waiter is deregistered.
+    fn wait_registered<T, F>(
+        mut space: MutexGuard<'_, Space<S>>,
+        condvar: &Condvar,
+        deadline: Option<Instant>,
+        mut operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut(&mut S) -> Result<Option<T>>,
+    {
+        loop {
+            space = match deadline {
+                None => condvar.wait(space)?,
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(Error::Timeout);
+                    }
+                    condvar.wait_timeout(space, deadline - now)?.0
+                }
+            };
+            if let Some(result) = operation(&mut space.store).transpose() {
+                return result;
+            }
+        }
+    }
+
+    /// Calls `operation` on the internal store once. If it finds no tuple, the task of
+    /// `context` is registered as `waiter` and woken when a tuple satisfying `query_tuple` is
+    /// written.
+    pub(crate) fn poll_for<F>(
+        &self,
+        query_tuple: &QueryTuple,
+        waiter: &mut Option<usize>,
+        context: &mut Context<'_>,
+        operation: F,
+    ) -> Poll<Result<Tuple>>
+    where
+        F: FnOnce(&mut S) -> Result<Option<Tuple>>,
+    {
+        let mut space = match self.lock() {
+            Ok(space) => space,
+            Err(error) => return Poll::Ready(Err(error)),
+        };
+        match operation(&mut space.store).transpose() {
+            Some(result) => {
+                if let Some(id) = waiter.take() {
+                    space.waiters.deregister(id);
+                }
+                Poll::Ready(result)
+            }
+            None => {
+                let wakeup = Wakeup::Task(context.waker().clone());
+                match waiter {
+                    Some(id) => space.waiters.update(*id, wakeup),
+                    None => {
+                        *waiter = Some(
+                            space
+                                .waiters
+                                .register(std::slice::from_ref(query_tuple), wakeup),
+                        )
+                    }
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Removes a waiter registered by [MutexStore::poll_for] that is no longer interested in
+    /// a tuple.
+    pub(crate) fn forget_waiter(&self, id: usize) {
+        if let Ok(mut space) = self.lock() {
+            space.waiters.deregister(id);
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Space<S>>> {
+        Ok(self.space.lock()?)
+    }
+}
+
+impl<S: Store> Clone for MutexStore<S> {
+    fn clone(&self) -> MutexStore<S> {
+        MutexStore {
+            space: self.space.clone(),
+        }
+    }
+}
+
+impl<S: Store> Default for MutexStore<S> {
+    fn default() -> MutexStore<S> {
+        MutexStore::new(S::default())
+    }
 }
 
 impl<S: Store> Store for MutexStore<S> {
     fn size(&self) -> Result<usize> {
-        self.store.lock()?.size()
+        self.lock()?.store.size()
     }
 
     fn write(&mut self, tuple: &Tuple) -> Result<()> {
-        Ok(self.store.lock()?.write(tuple)?)
+        let mut space = self.lock()?;
+        space.store.write(tuple)?;
+        space.waiters.notify(tuple);
+        Ok(())
     }
 
-    fn read(&self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
-        self.store.lock()?.read(query_tuple)
+    fn write_all<I>(&mut self, tuples: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Tuple>,
+    {
+        let mut space = self.lock()?;
+        let Space { store, waiters } = &mut *space;
+        store.write_all(tuples.into_iter().inspect(|tuple| waiters.notify(tuple)))
     }
 
-    fn take(&mut self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
-        self.store.lock()?.take(query_tuple)
+    fn read_now(&self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
+        self.lock()?.store.read_now(query_tuple)
+    }
+
+    fn take_now(&mut self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
+        self.lock()?.store.take_now(query_tuple)
+    }
+
+    fn take_many_now(&mut self, query_tuples: &[QueryTuple]) -> Result<Option<Vec<Tuple>>> {
+        self.lock()?.store.take_many_now(query_tuples)
+    }
+
+    fn read_all(&self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>> {
+        self.lock()?.store.read_all(query_tuple)
+    }
+
+    fn take_all(&mut self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>> {
+        self.lock()?.store.take_all(query_tuple)
+    }
+
+    fn write_with_lease(&mut self, tuple: &Tuple, duration: Duration) -> Result<LeaseId> {
+        let mut space = self.lock()?;
+        let lease_id = space.store.write_with_lease(tuple, duration)?;
+        space.waiters.notify(tuple);
+        Ok(lease_id)
+    }
+
+    fn renew_lease(&mut self, lease_id: LeaseId, duration: Duration) -> Result<bool> {
+        self.lock()?.store.renew_lease(lease_id, duration)
+    }
+
+    fn cancel_lease(&mut self, lease_id: LeaseId) -> Result<Option<Tuple>> {
+        self.lock()?.store.cancel_lease(lease_id)
+    }
+
+    fn purge_expired(&mut self) -> Result<Vec<Tuple>> {
+        self.lock()?.store.purge_expired()
     }
 }
 
@@ -76,7 +408,7 @@ fn test_mutex_store() -> Result<()> {
 
     let thread_tuple_space = tuple_space.clone();
     let test_thread = thread::spawn(move || {
-        match thread_tuple_space.read(&QueryTuple::builder().integer(2).build()) {
+        match thread_tuple_space.read_now(&QueryTuple::builder().integer(2).build()) {
             Ok(Some(_tuple)) => (),
             _ => panic!("No tuple found"),
         }
@@ -90,24 +422,252 @@ fn test_mutex_store() -> Result<()> {
     let exact_query_tuple = QueryTuple::builder().integer(5).build();
     let wildcard_query_tuple = QueryTuple::builder().any_integer().build();
 
-    match tuple_space.take(&exact_query_tuple)? {
+    match tuple_space.take_now(&exact_query_tuple)? {
         Some(_tuple) => (),
         None => panic!("No tuple found"),
     }
 
     assert_eq!(1, tuple_space.size()?);
 
-    match tuple_space.take(&wildcard_query_tuple)? {
+    match tuple_space.take_now(&wildcard_query_tuple)? {
         Some(_tuple) => (),
         None => panic!("No tuple found"),
     }
 
     assert_eq!(0, tuple_space.size()?);
 
-    match tuple_space.take(&wildcard_query_tuple)? {
-        Some(_tuple) => panic!("Tuple found"),
-        None => (),
+    if let Some(_tuple) = tuple_space.take_now(&wildcard_query_tuple)? {
+        panic!("Tuple found")
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_mutex_store_all() -> Result<()> {
+    use crate::vec_store::VecStore;
+
+    let mut tuple_space = MutexStore::<VecStore>::default();
+    for i in 0..3 {
+        tuple_space.write(&Tuple::builder().string("job").integer(i).build())?;
+    }
+    tuple_space.write(&Tuple::builder().string("result").integer(0).build())?;
+
+    let query_tuple = QueryTuple::builder().string("job").any_integer().build();
+    assert_eq!(3, tuple_space.clone().read_all(&query_tuple)?.len());
+    assert_eq!(3, tuple_space.take_all(&query_tuple)?.len());
+    assert_eq!(1, tuple_space.size()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_mutex_store_write_all() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::thread;
+
+    let mut tuple_space = MutexStore::<VecStore>::default();
+    let query_tuple = QueryTuple::builder().any_integer().build();
+
+    let reader_tuple_space = tuple_space.clone();
+    let reader_query_tuple = query_tuple.clone();
+    let reader_thread = thread::spawn(move || -> Result<()> {
+        for _ in 0..100 {
+            let count = reader_tuple_space.read_all(&reader_query_tuple)?.len();
+            assert_eq!(0, count % 100);
+        }
+        Ok(())
+    });
+
+    for _ in 0..10 {
+        tuple_space.write_all((0..100).map(|i| Tuple::builder().integer(i).build()))?;
+    }
+    reader_thread.join().expect("Reader panic")?;
+    assert_eq!(1000, tuple_space.read_all(&query_tuple)?.len());
+
+    Ok(())
+}
+
+#[test]
+fn test_mutex_store_blocking() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::{thread, time};
+
+    let mut tuple_space = MutexStore::<VecStore>::default();
+
+    let mut taker_tuple_space = tuple_space.clone();
+    let taker_thread =
+        thread::spawn(move || taker_tuple_space.take(&QueryTuple::builder().integer(2).build()));
+    let reader_tuple_space = tuple_space.clone();
+    let reader_thread =
+        thread::spawn(move || reader_tuple_space.read(&QueryTuple::builder().any_string().build()));
+
+    thread::sleep(time::Duration::from_millis(50));
+    tuple_space.write(&Tuple::builder().integer(1).build())?;
+    tuple_space.write(&Tuple::builder().integer(2).build())?;
+    tuple_space.write(&Tuple::builder().string("S").build())?;
+
+    let taken = taker_thread.join().expect("Taker panic")?;
+    assert_eq!(QueryTuple::builder().integer(2).build(), taken);
+    let read = reader_thread.join().expect("Reader panic")?;
+    assert_eq!(QueryTuple::builder().string("S").build(), read);
+
+    assert_eq!(2, tuple_space.size()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_mutex_store_timeout() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::thread;
+
+    let mut tuple_space = MutexStore::<VecStore>::default();
+    let query_tuple = QueryTuple::builder().integer(1).build();
+    let timeout = Duration::from_millis(20);
+
+    match tuple_space.take_timeout(&query_tuple, timeout) {
+        Err(Error::Timeout) => (),
+        result => panic!("Expected timeout, got {:?}", result),
+    }
+    match tuple_space.read_until(&query_tuple, Instant::now() + timeout) {
+        Err(Error::Timeout) => (),
+        result => panic!("Expected timeout, got {:?}", result),
+    }
+
+    let mut writer_tuple_space = tuple_space.clone();
+    let writer_thread = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        writer_tuple_space.write(&Tuple::builder().integer(1).build())
+    });
+
+    tuple_space.read_timeout(&query_tuple, Duration::MAX)?;
+    tuple_space.take_until(&query_tuple, Instant::now() + Duration::from_secs(10))?;
+    writer_thread.join().expect("Writer panic")?;
+
+    tuple_space.write(&Tuple::builder().integer(1).build())?;
+    tuple_space.take_timeout(&query_tuple, Duration::MAX)?;
+
+    assert_eq!(0, tuple_space.size()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_mutex_store_take_many() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::thread;
+
+    let mut tuple_space = MutexStore::<VecStore>::default();
+    let query_tuples = [
+        QueryTuple::builder().string("order").integer(1).build(),
+        QueryTuple::builder().string("payment").integer(1).build(),
+    ];
+
+    let mut taker_tuple_space = tuple_space.clone();
+    let taker_query_tuples = query_tuples.clone();
+    let taker_thread = thread::spawn(move || taker_tuple_space.take_many(&taker_query_tuples));
+
+    tuple_space.write(&Tuple::builder().string("order").integer(1).build())?;
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(1, tuple_space.size()?);
+    tuple_space.write(&Tuple::builder().string("payment").integer(1).build())?;
+
+    let tuples = taker_thread.join().expect("Taker panic")?;
+    assert_eq!(query_tuples[0], tuples[0]);
+    assert_eq!(query_tuples[1], tuples[1]);
+    assert_eq!(0, tuple_space.size()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_mutex_store_bound() -> Result<()> {
+    use crate::types::Types;
+    use crate::vec_store::VecStore;
+
+    let mut tuple_space = MutexStore::<VecStore>::default();
+    let self_loop = QueryTuple::builder()
+        .variable("x")
+        .string("edge")
+        .variable("x")
+        .build();
+
+    let mut writer = tuple_space.clone();
+    let handle = std::thread::spawn(move || -> Result<()> {
+        writer.write(
+            &Tuple::builder()
+                .string("a")
+                .string("edge")
+                .string("b")
+                .build(),
+        )?;
+        writer.write(
+            &Tuple::builder()
+                .string("c")
+                .string("edge")
+                .string("c")
+                .build(),
+        )
+    });
+
+    let (tuple, bindings) = tuple_space.take_bound(&self_loop)?;
+    handle.join().unwrap()?;
+    assert_eq!(self_loop, tuple);
+    assert!(matches!(bindings.get("x"), Some(Types::String(node)) if node == "c"));
+    assert_eq!(1, tuple_space.size()?);
+    assert!(tuple_space.read_bound_now(&self_loop)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_mutex_store_waiter_deregistered() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::thread;
+
+    let tuple_space = MutexStore::<VecStore>::default();
+    let query_tuple = QueryTuple::builder().integer(1).build();
+    let waiter_count = |tuple_space: &MutexStore<VecStore>| {
+        tuple_space
+            .space
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .waiters
+            .waiters
+            .len()
+    };
+
+    assert!(tuple_space
+        .read_timeout(&query_tuple, Duration::from_millis(10))
+        .is_err());
+    assert_eq!(0, waiter_count(&tuple_space));
+
+    let reader_tuple_space = tuple_space.clone();
+    let reader_query_tuple = query_tuple.clone();
+    let reader_thread = thread::spawn(move || reader_tuple_space.read(&reader_query_tuple));
+    while waiter_count(&tuple_space) == 0 {
+        thread::yield_now();
+    }
+
+    let poisoner_space = tuple_space.space.clone();
+    let poisoner_thread = thread::spawn(move || {
+        let _space = poisoner_space.lock();
+        panic!("Poisoner panic");
+    });
+    assert!(poisoner_thread.join().is_err());
+    tuple_space
+        .space
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .waiters
+        .notify(&Tuple::builder().integer(1).build());
+
+    match reader_thread.join().expect("Reader panic") {
+        Err(Error::MutexPoisonError) => (),
+        result => panic!("Expected poison error, got {:?}", result),
     }
+    assert_eq!(0, waiter_count(&tuple_space));
 
     Ok(())
 }
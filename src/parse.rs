@@ -0,0 +1,541 @@
+use crate::error::Error;
+use crate::query_tuple::QueryTuple;
+use crate::result::Result;
+use crate::timestamp::parse_nanoseconds;
+use crate::tuple::Tuple;
+use crate::types::{Decimal, QueryTypes, Types};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Parses a tuple written like the [std::fmt::Display] output of [Tuple], e.g.
+/// `(1, 2.5, true, "foo")`.
+///
+/// Elements are written as:
+/// * integers: `5`, `-5`, with a suffix for other integer types: `5u64`, `5i128`
+/// * floats with a fraction or exponent: `2.5`, `1.0`, `1e-7`, `NaN`, `inf`, `-inf`
+/// * decimals: `12.50d`
+/// * booleans: `true`, `false`
+/// * strings, quoted with Rust escapes: `"foo"`, `"say \"hi\"\n"`
+/// * bytes in hexadecimal: `0x00ff`
+/// * timestamps in RFC 3339 UTC form: `2021-03-01T12:30:00.5Z`
+/// * durations in ISO 8601 seconds: `PT1.5S`
+/// * nested tuples: `(1, 2)`, lists: `[1, 2]`, maps: `{"key": 1}`
+/// * `null`
+///
+/// Tuples, lists and maps can be nested at most [MAX_DEPTH] levels deep.
+impl FromStr for Tuple {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Tuple> {
+        let mut parser = Parser::new(text);
+        let tuple = parser.tuple()?;
+        parser.end()?;
+        Ok(tuple)
+    }
+}
+
+/// Parses a query tuple template such as `(?int, 5, "foo", *)`. Values are written as for
+/// [Tuple] and match exactly, and nested tuples are templates themselves. In addition:
+/// * `_` matches any element
+/// * `?int`, `?float`, `?bool`, `?string`, `?bytes`, `?tuple`, `?list`, `?map`, `?timestamp`,
+///   `?duration`, `?u64`, `?i128` and `?decimal` match any element of that type
+/// * `?name`, for any other name, is a variable (see [QueryTuple::bind])
+/// * `*`, only as the last element, matches any further elements
+impl FromStr for QueryTuple {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<QueryTuple> {
+        let mut parser = Parser::new(text);
+        let query_tuple = parser.query_tuple()?;
+        parser.end()?;
+        Ok(query_tuple)
+    }
+}
+
+/// The deepest nesting of tuples, lists and maps that is parsed, so that deeply nested input
+/// returns an error instead of overflowing the stack.
+pub const MAX_DEPTH: usize = 128;
+
+/// Characters ending a word, besides whitespace.
+const DELIMITERS: &[char] = &[',', '(', ')', '[', ']', '{', '}', '"'];
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Parser<'a> {
+        Parser {
+            text,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    fn error<T>(&self, reason: &str) -> Result<T> {
+        Err(Error::InvalidSyntax(format!(
+            "{} at position {}",
+            reason, self.position
+        )))
+    }
+
+    /// The next character that is not whitespace, which is skipped.
+    fn peek(&mut self) -> Option<char> {
+        let remaining = &self.text[self.position..];
+        let trimmed = remaining.trim_start();
+        self.position += remaining.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.position += expected.len_utf8();
+        }
+        found
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            self.error(&format!("expected '{}'", expected))
+        }
+    }
+
+    fn end(&mut self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => self.error("unexpected text after the tuple"),
+        }
+    }
+
+    /// Parses items separated by `,` up to the `close` character, the opening character having
+    /// been consumed already. Every nested tuple, list and map is parsed as a sequence, so this
+    /// is where the nesting depth is limited to [MAX_DEPTH].
+    fn sequence<T, F>(&mut self, close: char, item: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&mut Parser<'a>) -> Result<T>,
+    {
+        if self.depth == MAX_DEPTH {
+            return self.error(&format!("nesting deeper than {} levels", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let items = self.sequence_items(close, item);
+        self.depth -= 1;
+        items
+    }
+
+    fn sequence_items<T, F>(&mut self, close: char, mut item: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&mut Parser<'a>) -> Result<T>,
+    {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    /// The characters up to the next delimiter or whitespace.
+    fn word(&mut self) -> &'a str {
+        self.peek();
+        let remaining = &self.text[self.position..];
+        let length = remaining
+            .find(|character: char| character.is_whitespace() || DELIMITERS.contains(&character))
+            .unwrap_or(remaining.len());
+        self.position += length;
+        &remaining[..length]
+    }
+
+    fn tuple(&mut self) -> Result<Tuple> {
+        self.expect('(')?;
+        Ok(Tuple::new(self.sequence(')', Parser::element)?))
+    }
+
+    fn element(&mut self) -> Result<Types> {
+        match self.peek() {
+            Some('(') => Ok(Types::Tuple(self.tuple()?)),
+            Some('[') => {
+                self.expect('[')?;
+                Ok(Types::List(self.sequence(']', Parser::element)?))
+            }
+            Some('{') => {
+                self.expect('{')?;
+                let entries = self.sequence('}', |parser| {
+                    let key = parser.string()?;
+                    parser.expect(':')?;
+                    Ok((key, parser.element()?))
+                })?;
+                Ok(Types::Map(entries.into_iter().collect()))
+            }
+            Some('"') => Ok(Types::String(self.string()?)),
+            Some(_) => {
+                let position = self.position;
+                let word = self.word();
+                match scalar(word) {
+                    Some(element) => Ok(element),
+                    None => {
+                        self.position = position;
+                        self.error(&format!("invalid element '{}'", word))
+                    }
+                }
+            }
+            None => self.error("expected an element"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        let mut characters = self.text[self.position..].char_indices();
+        while let Some((offset, character)) = characters.next() {
+            let escaped = match character {
+                '"' => {
+                    self.position += offset + 1;
+                    return Ok(string);
+                }
+                '\\' => characters.next().map(|(_, escape)| escape),
+                character => {
+                    string.push(character);
+                    continue;
+                }
+            };
+            let unescaped = match escaped {
+                Some('n') => Some('\n'),
+                Some('r') => Some('\r'),
+                Some('t') => Some('\t'),
+                Some('0') => Some('\0'),
+                Some(escape @ ('"' | '\'' | '\\')) => Some(escape),
+                Some('u') => unicode_escape(&mut characters),
+                _ => None,
+            };
+            match unescaped {
+                Some(character) => string.push(character),
+                None => {
+                    self.position += offset;
+                    return self.error("invalid escape sequence");
+                }
+            }
+        }
+        self.error("unterminated string")
+    }
+
+    fn query_tuple(&mut self) -> Result<QueryTuple> {
+        self.expect('(')?;
+        let mut query_types = self.sequence(')', |parser| {
+            if parser.eat('*') {
+                Ok(None)
+            } else {
+                parser.query_type().map(Some)
+            }
+        })?;
+        let rest = match query_types.last() {
+            Some(None) => {
+                query_types.pop();
+                Some(QueryTypes::Any)
+            }
+            _ => None,
+        };
+        match query_types.into_iter().collect() {
+            Some(query_types) => Ok(QueryTuple::new(query_types, rest)),
+            None => self.error("'*' must be the last element"),
+        }
+    }
+
+    fn query_type(&mut self) -> Result<QueryTypes> {
+        match self.peek() {
+            Some('(') => Ok(QueryTypes::ExactTuple(self.query_tuple()?)),
+            Some('?') => {
+                self.expect('?')?;
+                let position = self.position;
+                let name = self.word();
+                if let Some(query_type) = any_type(name) {
+                    Ok(query_type)
+                } else if !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|character| character.is_alphanumeric() || character == '_')
+                {
+                    Ok(QueryTypes::Variable(String::from(name)))
+                } else {
+                    self.position = position;
+                    self.error(&format!("invalid variable name '{}'", name))
+                }
+            }
+            Some('_') if self.word() == "_" => Ok(QueryTypes::Any),
+            Some('_') => self.error("expected '_' to be followed by ',' or ')'"),
+            _ => Ok(QueryTypes::from(&self.element()?)),
+        }
+    }
+}
+
+/// The character of a `\u{...}` escape, the `u` having been consumed.
+fn unicode_escape(characters: &mut std::str::CharIndices) -> Option<char> {
+    if characters.next()?.1 != '{' {
+        return None;
+    }
+    let mut code = String::new();
+    for (_, character) in characters.by_ref() {
+        if character == '}' {
+            return char::from_u32(u32::from_str_radix(&code, 16).ok()?);
+        }
+        code.push(character);
+    }
+    None
+}
+
+/// The wildcard query type written as `?name`.
+fn any_type(name: &str) -> Option<QueryTypes> {
+    let query_type = match name {
+        "int" => QueryTypes::AnyInteger,
+        "float" => QueryTypes::AnyFloat,
+        "bool" => QueryTypes::AnyBoolean,
+        "string" => QueryTypes::AnyString,
+        "bytes" => QueryTypes::AnyBytes,
+        "tuple" => QueryTypes::AnyTuple,
+        "list" => QueryTypes::AnyList,
+        "map" => QueryTypes::AnyMap,
+        "timestamp" => QueryTypes::AnyTimestamp,
+        "duration" => QueryTypes::AnyDuration,
+        "u64" => QueryTypes::AnyUnsignedInteger,
+        "i128" => QueryTypes::AnyInteger128,
+        "decimal" => QueryTypes::AnyDecimal,
+        _ => return None,
+    };
+    Some(query_type)
+}
+
+/// An element written without quotes or brackets.
+fn scalar(word: &str) -> Option<Types> {
+    let element = match word {
+        "true" => Types::Boolean(true),
+        "false" => Types::Boolean(false),
+        "null" => Types::Null,
+        "NaN" => Types::Float(f64::NAN),
+        "inf" => Types::Float(f64::INFINITY),
+        "-inf" => Types::Float(f64::NEG_INFINITY),
+        _ => {
+            if let Some(hex) = word.strip_prefix("0x") {
+                Types::Bytes(bytes(hex)?)
+            } else if let Some(seconds) = word.strip_prefix("PT") {
+                Types::Duration(duration(seconds.strip_suffix('S')?)?)
+            } else if word.contains('T') {
+                Types::Timestamp(word.parse().ok()?)
+            } else if let Some(integer) = word.strip_suffix("u64") {
+                Types::UnsignedInteger(integer.parse().ok()?)
+            } else if let Some(integer) = word.strip_suffix("i128") {
+                Types::Integer128(integer.parse().ok()?)
+            } else if let Some(decimal) = word.strip_suffix('d') {
+                Types::Decimal(Decimal::from_str(decimal).ok()?)
+            } else if word.contains(['.', 'e', 'E']) {
+                Types::Float(word.parse().ok()?)
+            } else {
+                Types::Integer(word.parse().ok()?)
+            }
+        }
+    };
+    Some(element)
+}
+
+fn bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+fn duration(seconds: &str) -> Option<Duration> {
+    let (seconds, nanoseconds) = match seconds.split_once('.') {
+        Some((seconds, fraction)) => (seconds, parse_nanoseconds(fraction)?),
+        None => (seconds, 0),
+    };
+    if !seconds.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some(Duration::new(seconds.parse().ok()?, nanoseconds))
+}
+
+#[test]
+fn test_parse_tuple() -> Result<()> {
+    use crate::timestamp::Timestamp;
+    use std::collections::BTreeMap;
+
+    let tuple = Tuple::builder()
+        .integer(-1)
+        .float(2.5)
+        .float(1.0)
+        .boolean(true)
+        .string("say \"hi\"\n\\ \u{7f}")
+        .bytes(&[0, 255])
+        .tuple(&Tuple::builder().string("nested").build())
+        .list(&[Types::Integer(1), Types::String(String::from("S1"))])
+        .map(&BTreeMap::from([(
+            String::from("key: \"quoted\""),
+            Types::Null,
+        )]))
+        .timestamp(Timestamp::from_unix(1_614_601_800, 500_000_000))
+        .duration(Duration::new(1, 500_000_000))
+        .unsigned_integer(u64::MAX)
+        .integer_128(i128::MIN)
+        .decimal(Decimal::new(1_250, 2))
+        .null()
+        .build();
+    let text = format!("{}", tuple);
+    assert_eq!(tuple, text.parse::<Tuple>()?);
+    assert_eq!(text, format!("{}", text.parse::<Tuple>()?));
+
+    let tuple: Tuple = " ( 1 ,2.5,  true, \"foo\" ) ".parse()?;
+    assert_eq!(
+        Tuple::builder()
+            .integer(1)
+            .float(2.5)
+            .boolean(true)
+            .string("foo")
+            .build(),
+        tuple
+    );
+    assert!("()".parse::<Tuple>()?.is_empty());
+
+    let tuple: Tuple = "(NaN, -inf, 1e-7, \"\\u{1F600}\")".parse()?;
+    assert!(matches!(tuple[0], Types::Float(float) if float.is_nan()));
+    assert_eq!(Types::Float(f64::NEG_INFINITY), tuple[1]);
+    assert_eq!(Types::Float(1e-7), tuple[2]);
+    assert_eq!(Types::String(String::from("\u{1F600}")), tuple[3]);
+
+    let zero = std::hint::black_box(0.0);
+    let computed_nan = Tuple::builder().float(zero / zero).float(-f64::NAN).build();
+    assert_eq!("(NaN, NaN)", format!("{}", computed_nan));
+    assert_eq!(computed_nan, format!("{}", computed_nan).parse::<Tuple>()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_query_tuple() -> Result<()> {
+    let tuple = Tuple::builder()
+        .integer(1)
+        .integer(5)
+        .string("foo")
+        .tuple(&Tuple::builder().integer(2).integer(1).build())
+        .boolean(true)
+        .build();
+
+    let query_tuple: QueryTuple = "(?int, 5, \"foo\", *)".parse()?;
+    assert_eq!(3, query_tuple.len());
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple: QueryTuple = "(?x, _, ?string, (_, ?x), ?bool)".parse()?;
+    assert_eq!(query_tuple, tuple);
+    assert_eq!(
+        Some(&Types::Integer(1)),
+        query_tuple.bind(&tuple).unwrap().get("x")
+    );
+
+    let query_tuple: QueryTuple = "(?x, _, ?string, (?x, _), ?bool)".parse()?;
+    assert_ne!(query_tuple, tuple);
+
+    let query_tuple: QueryTuple = "(?float, 5, \"foo\", *)".parse()?;
+    assert_ne!(query_tuple, tuple);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_errors() {
+    fn reason<T: FromStr<Err = Error> + std::fmt::Debug>(text: &str) -> String {
+        match text.parse::<T>() {
+            Err(Error::InvalidSyntax(reason)) => reason,
+            result => panic!("Expected a syntax error, got {:?}", result),
+        }
+    }
+
+    assert_eq!("expected '(' at position 0", reason::<Tuple>("1, 2"));
+    assert_eq!("expected ',' at position 3", reason::<Tuple>("(1 2)"));
+    assert_eq!(
+        "invalid element 'foo' at position 1",
+        reason::<Tuple>("(foo)")
+    );
+    assert_eq!(
+        "invalid element '0x0' at position 1",
+        reason::<Tuple>("(0x0)")
+    );
+    assert_eq!(
+        "unterminated string at position 2",
+        reason::<Tuple>("(\"foo)")
+    );
+    assert_eq!(
+        "invalid escape sequence at position 2",
+        reason::<Tuple>("(\"\\q\")")
+    );
+    assert_eq!(
+        "unexpected text after the tuple at position 4",
+        reason::<Tuple>("(1) 2")
+    );
+    assert_eq!("expected an element at position 3", reason::<Tuple>("(1,"));
+    assert_eq!(
+        "'*' must be the last element at position 6",
+        reason::<QueryTuple>("(*, 1)")
+    );
+    assert_eq!(
+        "invalid variable name '' at position 2",
+        reason::<QueryTuple>("(?, 1)")
+    );
+    assert_eq!(
+        "invalid element '99999999999999999-01-01T00:00:00Z' at position 1",
+        reason::<Tuple>("(99999999999999999-01-01T00:00:00Z)")
+    );
+    assert_eq!(
+        "invalid element '-9223372036854775808-01-01T00:00:00Z' at position 1",
+        reason::<Tuple>("(-9223372036854775808-01-01T00:00:00Z)")
+    );
+    assert_eq!(
+        "invalid element '2021-03-01T-9223372036854775808:00:00Z' at position 1",
+        reason::<Tuple>("(2021-03-01T-9223372036854775808:00:00Z)")
+    );
+}
+
+#[test]
+fn test_parse_depth() -> Result<()> {
+    let nested = |depth: usize| format!("{}{}", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(1, nested(MAX_DEPTH).parse::<Tuple>()?.len());
+    assert_eq!(1, nested(MAX_DEPTH).parse::<QueryTuple>()?.len());
+    assert_eq!(
+        1,
+        format!(
+            "({}1{})",
+            "[".repeat(MAX_DEPTH - 1),
+            "]".repeat(MAX_DEPTH - 1)
+        )
+        .parse::<Tuple>()?
+        .len()
+    );
+
+    let reason = format!(
+        "nesting deeper than {} levels at position {}",
+        MAX_DEPTH,
+        MAX_DEPTH + 1
+    );
+    for text in [nested(MAX_DEPTH + 1), nested(200_000)] {
+        assert!(
+            matches!(text.parse::<Tuple>(), Err(Error::InvalidSyntax(error)) if error == reason)
+        );
+        assert!(
+            matches!(text.parse::<QueryTuple>(), Err(Error::InvalidSyntax(error)) if error == reason)
+        );
+    }
+    let lists = format!("({}{})", "[".repeat(200_000), "]".repeat(200_000));
+    assert!(lists.parse::<Tuple>().is_err());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::error::Error;
+use crate::result::Result;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A regular expression compiled once when it is created, used by
+/// [QueryTypes::StringRegex](crate::types::QueryTypes::StringRegex).
+///
+/// Patterns are serialized as their source text and compiled again when deserialized.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: regex::Regex,
+}
+
+impl Pattern {
+    /// Compiles `pattern`, e.g. `^ERR-[0-9]{4}$`. Returns [Error::InvalidRegex] if it is not a
+    /// valid regular expression.
+    pub fn new(pattern: &str) -> Result<Pattern> {
+        regex::Regex::new(pattern)
+            .map(|regex| Pattern { regex })
+            .map_err(|error| Error::InvalidRegex(error.to_string()))
+    }
+
+    /// The source text of the pattern.
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// `true` if the pattern matches anywhere in `string`.
+    pub fn is_match(&self, string: &str) -> bool {
+        self.regex.is_match(string)
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Pattern, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        regex::Regex::new(&pattern)
+            .map(|regex| Pattern { regex })
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[test]
+fn test_pattern() {
+    let pattern = Pattern::new("^ERR-[0-9]{4}$").unwrap();
+    assert_eq!("^ERR-[0-9]{4}$", pattern.as_str());
+    assert!(pattern.is_match("ERR-0042"));
+    assert!(!pattern.is_match("ERR-42"));
+    assert!(!pattern.is_match("WARN-0042"));
+
+    assert!(matches!(
+        Pattern::new("ERR-[0-9"),
+        Err(Error::InvalidRegex(_))
+    ));
+}
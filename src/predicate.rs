@@ -0,0 +1,78 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::Arc;
+
+/// A user defined test of a value, used by
+/// [QueryTypes::Predicate](crate::types::QueryTypes::Predicate) for elements and by
+/// [QueryTupleBuilder::tuple_predicate](crate::query_tuple::QueryTupleBuilder::tuple_predicate)
+/// for whole tuples.
+///
+/// Predicates only exist within the process that created them: serializing or deserializing a
+/// predicate, and so a query tuple containing one, fails with an error.
+pub struct Predicate<T: ?Sized> {
+    function: Arc<dyn Fn(&T) -> bool + Send + Sync>,
+}
+
+impl<T: ?Sized> Predicate<T> {
+    pub fn new<F>(function: F) -> Predicate<T>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Predicate {
+            function: Arc::new(function),
+        }
+    }
+
+    /// `true` if `value` satisfies the predicate.
+    pub fn test(&self, value: &T) -> bool {
+        (self.function)(value)
+    }
+}
+
+impl<T: ?Sized> Clone for Predicate<T> {
+    fn clone(&self) -> Predicate<T> {
+        Predicate {
+            function: self.function.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized> std::fmt::Debug for Predicate<T> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "Predicate(..)")
+    }
+}
+
+impl<T: ?Sized> Serialize for Predicate<T> {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom(
+            "predicates cannot be serialized, they only exist in the process that created them",
+        ))
+    }
+}
+
+impl<'de, T: ?Sized> Deserialize<'de> for Predicate<T> {
+    fn deserialize<D: Deserializer<'de>>(
+        _deserializer: D,
+    ) -> std::result::Result<Predicate<T>, D::Error> {
+        Err(serde::de::Error::custom(
+            "predicates cannot be deserialized",
+        ))
+    }
+}
+
+#[test]
+fn test_predicate() {
+    use crate::types::Types;
+    use serde::de::IntoDeserializer;
+
+    let even = Predicate::new(|element: &Types| matches!(element, Types::Integer(i) if i % 2 == 0));
+    assert!(even.test(&Types::Integer(2)));
+    assert!(!even.clone().test(&Types::Integer(3)));
+    assert!(!even.test(&Types::Boolean(true)));
+    assert_eq!("Predicate(..)", format!("{:?}", even));
+
+    let deserializer: serde::de::value::StrDeserializer<serde::de::value::Error> =
+        "even".into_deserializer();
+    let error = Predicate::<Types>::deserialize(deserializer).unwrap_err();
+    assert_eq!("predicates cannot be deserialized", error.to_string());
+}
@@ -1,13 +1,41 @@
+use crate::pattern::Pattern;
+use crate::predicate::Predicate;
+use crate::result::Result;
+use crate::timestamp::Timestamp;
 use crate::tuple::Tuple;
-use crate::types::QueryTypes;
+use crate::types::Decimal;
+use crate::types::{QueryTypes, Types};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::{Bound, RangeBounds};
+use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct QueryTuple {
     query_tuple: Vec<QueryTypes>,
+    /// Matches every element after the fixed elements. Without it, the tuple must have exactly
+    /// as many elements as the query tuple.
+    #[serde(default)]
+    rest: Option<Box<QueryTypes>>,
+    /// Query tuples tried in order when this one does not match, see [QueryTuple::or].
+    #[serde(default)]
+    alternatives: Vec<QueryTuple>,
+    /// Tested against the whole tuple once all elements match.
+    #[serde(default)]
+    predicate: Option<Predicate<Tuple>>,
 }
 
 impl QueryTuple {
+    pub(crate) fn new(query_tuple: Vec<QueryTypes>, rest: Option<QueryTypes>) -> QueryTuple {
+        QueryTuple {
+            query_tuple,
+            rest: rest.map(Box::new),
+            alternatives: Vec::new(),
+            predicate: None,
+        }
+    }
+
+    /// The number of fixed elements, not counting a rest matcher.
     pub fn len(&self) -> usize {
         self.query_tuple.len()
     }
@@ -19,19 +47,179 @@ impl QueryTuple {
     pub fn builder() -> QueryTupleBuilder {
         QueryTupleBuilder::default()
     }
-}
 
-impl PartialEq<Tuple> for QueryTuple {
-    fn eq(&self, rhs: &Tuple) -> bool {
-        if self.len() != rhs.len() {
-            return false;
-        }
-        for i in 0..self.len() {
-            if self[i] != rhs[i] {
+    /// The query type matching the elements after the fixed elements, if the query tuple
+    /// matches tuples of varying length.
+    pub fn rest(&self) -> Option<&QueryTypes> {
+        self.rest.as_deref()
+    }
+
+    /// Also matches tuples matching `alternative`, which is tried after this query tuple and its
+    /// earlier alternatives. A store returns the first stored tuple matching any alternative, so
+    /// `("job", "email", *)` or `("job", "sms", *)` is taken with a single atomic `take`.
+    ///
+    /// [QueryTuple::len], [QueryTuple::rest] and indexing refer to the first query tuple only.
+    pub fn or(mut self, alternative: QueryTuple) -> QueryTuple {
+        self.alternatives.push(alternative);
+        self
+    }
+
+    /// Matches `tuple`, returning the values of the variables in the query tuple if it matches.
+    /// Each variable must be bound to equal elements wherever it occurs, so `(?x, "edge", ?x)`
+    /// only matches tuples whose first and last elements are equal.
+    pub fn bind(&self, tuple: &Tuple) -> Option<Bindings> {
+        let mut bindings = Bindings::default();
+        self.unify(tuple, &mut bindings).then_some(bindings)
+    }
+
+    pub(crate) fn unify(&self, tuple: &Tuple, bindings: &mut Bindings) -> bool {
+        self.unify_then(tuple, bindings, &mut |_| true)
+    }
+
+    /// Matches `tuple` like [QueryTuple::unify], then calls `then` with the extended bindings,
+    /// backtracking as described in [QueryTypes::unify_then].
+    /// `bindings` is only changed if `true` is returned.
+    pub(crate) fn unify_then(
+        &self,
+        tuple: &Tuple,
+        bindings: &mut Bindings,
+        then: &mut dyn FnMut(&mut Bindings) -> bool,
+    ) -> bool {
+        let length_matches = match &self.rest {
+            Some(_) => self.len() <= tuple.len(),
+            None => self.len() == tuple.len(),
+        };
+        (length_matches
+            && bindings.attempt(|bindings| self.unify_elements(tuple, 0, bindings, then)))
+            || self
+                .alternatives
+                .iter()
+                .any(|alternative| alternative.unify_then(tuple, bindings, then))
+    }
+
+    /// Unifies the elements of `tuple` from `index` on. Deterministic elements are unified in
+    /// place, so only the elements that may backtrack add to the recursion depth. Leaves partial
+    /// bindings behind when it returns `false`, so callers run it within [Bindings::attempt].
+    fn unify_elements(
+        &self,
+        tuple: &Tuple,
+        mut index: usize,
+        bindings: &mut Bindings,
+        then: &mut dyn FnMut(&mut Bindings) -> bool,
+    ) -> bool {
+        while index < tuple.len() {
+            let query_type = match self.query_tuple.get(index) {
+                Some(query_type) => query_type,
+                None => self.rest.as_deref().expect("length checked by unify_then"),
+            };
+            if !query_type.is_deterministic() {
+                return query_type.unify_then(&tuple[index], bindings, &mut |bindings| {
+                    bindings
+                        .attempt(|bindings| self.unify_elements(tuple, index + 1, bindings, then))
+                });
+            }
+            if !query_type.unify(&tuple[index], bindings) {
                 return false;
             }
+            index += 1;
         }
-        true
+        self.predicate
+            .as_ref()
+            .is_none_or(|predicate| predicate.test(tuple))
+            && then(bindings)
+    }
+
+    /// `true` if unifying may bind a variable, see [QueryTypes::binds_variables].
+    pub(crate) fn binds_variables(&self) -> bool {
+        self.query_tuple.iter().any(QueryTypes::binds_variables)
+            || self
+                .rest
+                .as_deref()
+                .is_some_and(QueryTypes::binds_variables)
+            || self.alternatives.iter().any(QueryTuple::binds_variables)
+    }
+
+    /// `false` if unifying may have to backtrack, see [QueryTypes::is_deterministic].
+    pub(crate) fn is_deterministic(&self) -> bool {
+        if !self.binds_variables() {
+            return true;
+        }
+        self.alternatives.is_empty()
+            && self.query_tuple.iter().all(QueryTypes::is_deterministic)
+            && self
+                .rest
+                .as_deref()
+                .is_none_or(QueryTypes::is_deterministic)
+    }
+}
+
+/// The values bound to the variables of a [QueryTuple] by a matching [Tuple].
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Bindings {
+    bindings: BTreeMap<String, Types>,
+}
+
+impl Bindings {
+    /// The value bound to the variable `name`.
+    pub fn get(&self, name: &str) -> Option<&Types> {
+        self.bindings.get(name)
+    }
+
+    /// The number of bound variables.
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// `true` if no variable is bound, `false` otherwise
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// An iterator over the variables and their values, ordered by name.
+    pub fn iter(&self) -> std::collections::btree_map::Iter<'_, String, Types> {
+        self.bindings.iter()
+    }
+
+    pub(crate) fn insert(&mut self, name: &str, value: Types) {
+        self.bindings.insert(String::from(name), value);
+    }
+
+    /// Calls `unify` on a copy of the bindings, keeping the copy only if it returns `true`, so
+    /// that a failed match binds nothing.
+    pub(crate) fn attempt<F>(&mut self, unify: F) -> bool
+    where
+        F: FnOnce(&mut Bindings) -> bool,
+    {
+        let mut bindings = self.clone();
+        let unified = unify(&mut bindings);
+        if unified {
+            *self = bindings;
+        }
+        unified
+    }
+}
+
+/// A query tuple matching exactly the elements of `tuple`.
+impl From<&Tuple> for QueryTuple {
+    fn from(tuple: &Tuple) -> QueryTuple {
+        QueryTuple {
+            query_tuple: tuple.iter().map(QueryTypes::from).collect(),
+            rest: None,
+            alternatives: Vec::new(),
+            predicate: None,
+        }
+    }
+}
+
+impl From<Tuple> for QueryTuple {
+    fn from(tuple: Tuple) -> QueryTuple {
+        QueryTuple::from(&tuple)
+    }
+}
+
+impl PartialEq<Tuple> for QueryTuple {
+    fn eq(&self, rhs: &Tuple) -> bool {
+        self.unify(rhs, &mut Bindings::default())
     }
 }
 
@@ -46,12 +234,37 @@ impl std::ops::Index<usize> for QueryTuple {
 #[derive(Default)]
 pub struct QueryTupleBuilder {
     query_tuple: Vec<QueryTypes>,
+    rest: Option<Box<QueryTypes>>,
+    predicate: Option<Predicate<Tuple>>,
 }
 
 impl QueryTupleBuilder {
     pub fn build(self) -> QueryTuple {
-        let QueryTupleBuilder { query_tuple } = self;
-        QueryTuple { query_tuple }
+        let QueryTupleBuilder {
+            query_tuple,
+            rest,
+            predicate,
+        } = self;
+        QueryTuple {
+            query_tuple,
+            rest,
+            alternatives: Vec::new(),
+            predicate,
+        }
+    }
+
+    /// Matches zero or more further elements of any type after the other elements, e.g.
+    /// `("event", *)` matches every tuple starting with `"event"`.
+    pub fn rest(self) -> Self {
+        self.rest_of(QueryTypes::Any)
+    }
+
+    /// Matches zero or more further elements after the other elements, each satisfying
+    /// `query_type`, e.g. `rest_of(QueryTypes::AnyInteger)`. The rest always follows the other
+    /// elements, however the builder calls are ordered.
+    pub fn rest_of(mut self, query_type: QueryTypes) -> Self {
+        self.rest = Some(Box::new(query_type));
+        self
     }
 
     pub fn any(mut self) -> Self {
@@ -59,6 +272,14 @@ impl QueryTupleBuilder {
         self
     }
 
+    /// Matches any element, binding it to the variable `name`. Every occurrence of the same
+    /// variable must match equal elements.
+    pub fn variable(mut self, name: &str) -> Self {
+        self.query_tuple
+            .push(QueryTypes::Variable(String::from(name)));
+        self
+    }
+
     pub fn any_integer(mut self) -> Self {
         self.query_tuple.push(QueryTypes::AnyInteger);
         self
@@ -69,6 +290,15 @@ impl QueryTupleBuilder {
         self
     }
 
+    /// Matches an integer within `range`, e.g. `5..=10`.
+    pub fn integer_range<R: RangeBounds<i64>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::IntegerRange(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
     pub fn any_float(mut self) -> Self {
         self.query_tuple.push(QueryTypes::AnyFloat);
         self
@@ -79,6 +309,32 @@ impl QueryTupleBuilder {
         self
     }
 
+    /// Matches a float within `abs_eps` of `value`, or within `rel_eps` relative to the larger
+    /// magnitude, e.g. `float_approx(0.3, 1e-9, 0.0)` matches `0.1 + 0.2`.
+    pub fn float_approx(mut self, value: f64, abs_eps: f64, rel_eps: f64) -> Self {
+        self.query_tuple.push(QueryTypes::FloatApprox {
+            value,
+            abs_eps,
+            rel_eps,
+        });
+        self
+    }
+
+    /// Matches a float that is `NaN`.
+    pub fn float_nan(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::FloatNaN);
+        self
+    }
+
+    /// Matches a float within `range`, e.g. `0.0..1.0`.
+    pub fn float_range<R: RangeBounds<f64>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::FloatRange(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
     pub fn any_boolean(mut self) -> Self {
         self.query_tuple.push(QueryTypes::AnyBoolean);
         self
@@ -99,4 +355,272 @@ impl QueryTupleBuilder {
             .push(QueryTypes::ExactString(String::from(string)));
         self
     }
+
+    /// Matches a string starting with `prefix`.
+    pub fn string_prefix(mut self, prefix: &str) -> Self {
+        self.query_tuple
+            .push(QueryTypes::StringPrefix(String::from(prefix)));
+        self
+    }
+
+    /// Matches a string ending with `suffix`.
+    pub fn string_suffix(mut self, suffix: &str) -> Self {
+        self.query_tuple
+            .push(QueryTypes::StringSuffix(String::from(suffix)));
+        self
+    }
+
+    /// Matches a string containing `substring`.
+    pub fn string_contains(mut self, substring: &str) -> Self {
+        self.query_tuple
+            .push(QueryTypes::StringContains(String::from(substring)));
+        self
+    }
+
+    /// Matches a string against a glob `pattern`, e.g. `"sensor/*/temp"`.
+    pub fn string_glob(mut self, pattern: &str) -> Self {
+        self.query_tuple
+            .push(QueryTypes::StringGlob(String::from(pattern)));
+        self
+    }
+
+    /// Matches a string in which the regular expression `pattern` matches, e.g. `^ERR-[0-9]{4}$`.
+    /// The pattern is compiled once, returning [Error::InvalidRegex](crate::error::Error::InvalidRegex)
+    /// if it is not valid.
+    pub fn string_regex(mut self, pattern: &str) -> Result<Self> {
+        self.query_tuple
+            .push(QueryTypes::StringRegex(Pattern::new(pattern)?));
+        Ok(self)
+    }
+
+    /// Matches a string within `range` in lexical order, e.g. `"a".."n"`.
+    pub fn string_range<'a, R: RangeBounds<&'a str>>(mut self, range: R) -> Self {
+        let to_string = |bound: Bound<&&str>| bound.map(|string| String::from(*string));
+        self.query_tuple.push(QueryTypes::StringRange(
+            to_string(range.start_bound()),
+            to_string(range.end_bound()),
+        ));
+        self
+    }
+
+    pub fn any_bytes(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyBytes);
+        self
+    }
+
+    pub fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.query_tuple
+            .push(QueryTypes::ExactBytes(bytes.to_vec()));
+        self
+    }
+
+    pub fn any_tuple(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyTuple);
+        self
+    }
+
+    /// Matches a nested tuple satisfying `query_tuple`.
+    pub fn tuple(mut self, query_tuple: QueryTuple) -> Self {
+        self.query_tuple.push(QueryTypes::ExactTuple(query_tuple));
+        self
+    }
+
+    pub fn any_list(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyList);
+        self
+    }
+
+    pub fn list(mut self, list: &[Types]) -> Self {
+        self.query_tuple.push(QueryTypes::ExactList(list.to_vec()));
+        self
+    }
+
+    /// Matches a list with at least one element satisfying `query_type`.
+    pub fn list_contains(mut self, query_type: QueryTypes) -> Self {
+        self.query_tuple
+            .push(QueryTypes::ListContains(Box::new(query_type)));
+        self
+    }
+
+    /// Matches a list whose length lies within `range`, e.g. `2..=4`.
+    pub fn list_length<R: RangeBounds<usize>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::ListLength(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    pub fn any_map(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyMap);
+        self
+    }
+
+    pub fn map(mut self, map: &BTreeMap<String, Types>) -> Self {
+        self.query_tuple.push(QueryTypes::ExactMap(map.clone()));
+        self
+    }
+
+    /// Matches a map containing `key` with a value satisfying `query_type`.
+    pub fn map_entry(mut self, key: &str, query_type: QueryTypes) -> Self {
+        self.query_tuple.push(QueryTypes::MapEntry(
+            String::from(key),
+            Box::new(query_type),
+        ));
+        self
+    }
+
+    /// Matches a map whose number of entries lies within `range`, e.g. `1..`.
+    pub fn map_length<R: RangeBounds<usize>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::MapLength(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    pub fn any_timestamp(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyTimestamp);
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.query_tuple.push(QueryTypes::ExactTimestamp(timestamp));
+        self
+    }
+
+    /// Matches a timestamp within `range`, e.g. `start..end`.
+    pub fn timestamp_range<R: RangeBounds<Timestamp>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::TimestampRange(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    pub fn any_duration(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyDuration);
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.query_tuple.push(QueryTypes::ExactDuration(duration));
+        self
+    }
+
+    /// Matches a duration within `range`, e.g. `..Duration::from_secs(60)`.
+    pub fn duration_range<R: RangeBounds<Duration>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::DurationRange(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    pub fn any_unsigned_integer(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyUnsignedInteger);
+        self
+    }
+
+    pub fn unsigned_integer(mut self, integer: u64) -> Self {
+        self.query_tuple
+            .push(QueryTypes::ExactUnsignedInteger(integer));
+        self
+    }
+
+    /// Matches an unsigned integer within `range`.
+    pub fn unsigned_integer_range<R: RangeBounds<u64>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::UnsignedIntegerRange(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    pub fn any_integer_128(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyInteger128);
+        self
+    }
+
+    pub fn integer_128(mut self, integer: i128) -> Self {
+        self.query_tuple.push(QueryTypes::ExactInteger128(integer));
+        self
+    }
+
+    /// Matches a 128 bit integer within `range`.
+    pub fn integer_128_range<R: RangeBounds<i128>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::Integer128Range(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    pub fn any_decimal(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::AnyDecimal);
+        self
+    }
+
+    /// Matches a decimal equal in value to `decimal`, so `1.5` matches `1.50`.
+    pub fn decimal(mut self, decimal: Decimal) -> Self {
+        self.query_tuple.push(QueryTypes::ExactDecimal(decimal));
+        self
+    }
+
+    /// Matches a decimal within `range`.
+    pub fn decimal_range<R: RangeBounds<Decimal>>(mut self, range: R) -> Self {
+        self.query_tuple.push(QueryTypes::DecimalRange(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    pub fn null(mut self) -> Self {
+        self.query_tuple.push(QueryTypes::ExactNull);
+        self
+    }
+
+    /// Matches an element satisfying any of `query_types`, e.g.
+    /// `one_of(vec![QueryTypes::ExactString(..), QueryTypes::ExactString(..)])`.
+    pub fn one_of(mut self, query_types: Vec<QueryTypes>) -> Self {
+        self.query_tuple.push(QueryTypes::OneOf(query_types));
+        self
+    }
+
+    /// Matches an element not satisfying `query_type`.
+    pub fn not(mut self, query_type: QueryTypes) -> Self {
+        self.query_tuple.push(QueryTypes::Not(Box::new(query_type)));
+        self
+    }
+
+    /// Matches an element for which `predicate` returns `true`, e.g. `|id| matches!(id,
+    /// Types::Integer(id) if id % 2 == 0)`. The resulting query tuple cannot be serialized.
+    pub fn predicate<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Types) -> bool + Send + Sync + 'static,
+    {
+        self.query_tuple
+            .push(QueryTypes::Predicate(Predicate::new(predicate)));
+        self
+    }
+
+    /// Only matches tuples for which `predicate` returns `true`, in addition to matching every
+    /// element. As the predicate is tested while the store is locked, a `take` removes a tuple
+    /// only if it passes. The resulting query tuple cannot be serialized.
+    pub fn tuple_predicate<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Tuple) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Some(Predicate::new(predicate));
+        self
+    }
+
+    /// Matches either a null element or an element satisfying `query_type`, e.g.
+    /// `optional(QueryTypes::AnyInteger)`.
+    pub fn optional(mut self, query_type: QueryTypes) -> Self {
+        self.query_tuple
+            .push(QueryTypes::Optional(Box::new(query_type)));
+        self
+    }
 }
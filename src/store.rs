@@ -1,6 +1,8 @@
-use crate::query_tuple::QueryTuple;
+use crate::lease::LeaseId;
+use crate::query_tuple::{Bindings, QueryTuple};
 use crate::result::Result;
 use crate::tuple::Tuple;
+use std::time::Duration;
 
 pub trait Store: Default {
     /// The number of tuples in the store.
@@ -9,11 +11,67 @@ pub trait Store: Default {
     /// Writes a tuple into the store.
     fn write(&mut self, tuple: &Tuple) -> Result<()>;
 
+    /// Writes a batch of tuples into the store in a single operation.
+    fn write_all<I>(&mut self, tuples: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Tuple>;
+
     /// Reads a tuple from the store, matching the query tuple. Does not remove the tuple from
-    /// the store.
-    fn read(&self, query_tuple: &QueryTuple) -> Result<Option<Tuple>>;
+    /// the store. Returns [None] immediately if no tuple matches (Linda `rdp`).
+    fn read_now(&self, query_tuple: &QueryTuple) -> Result<Option<Tuple>>;
 
     /// Reads a tuple from the store, matching the query tuple. Removes the tuple from
+    /// the store. Returns [None] immediately if no tuple matches (Linda `inp`).
+    fn take_now(&mut self, query_tuple: &QueryTuple) -> Result<Option<Tuple>>;
+
+    /// Like [Store::read_now], but also returns the values bound to the variables of the query
+    /// tuple.
+    fn read_bound_now(&self, query_tuple: &QueryTuple) -> Result<Option<(Tuple, Bindings)>> {
+        Ok(self
+            .read_now(query_tuple)?
+            .map(|tuple| with_bindings(query_tuple, tuple)))
+    }
+
+    /// Like [Store::take_now], but also returns the values bound to the variables of the query
+    /// tuple.
+    fn take_bound_now(&mut self, query_tuple: &QueryTuple) -> Result<Option<(Tuple, Bindings)>> {
+        Ok(self
+            .take_now(query_tuple)?
+            .map(|tuple| with_bindings(query_tuple, tuple)))
+    }
+
+    /// Removes one distinct tuple for each query tuple from the store, returned in the order of
+    /// the query tuples. If not every query tuple can be matched, no tuple is removed and [None]
+    /// is returned.
+    fn take_many_now(&mut self, query_tuples: &[QueryTuple]) -> Result<Option<Vec<Tuple>>>;
+
+    /// Reads all tuples from the store matching the query tuple. Does not remove the tuples from
     /// the store.
-    fn take(&mut self, query_tuple: &QueryTuple) -> Result<Option<Tuple>>;
+    fn read_all(&self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>>;
+
+    /// Reads all tuples from the store matching the query tuple. Removes the tuples from the
+    /// store in a single operation.
+    fn take_all(&mut self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>>;
+
+    /// Writes a tuple into the store that expires after `duration`. Expired tuples can no longer
+    /// be read or taken. A `duration` too long to represent as an
+    /// [Instant](std::time::Instant) never expires.
+    fn write_with_lease(&mut self, tuple: &Tuple, duration: Duration) -> Result<LeaseId>;
+
+    /// Extends a lease so that its tuple expires `duration` from now. Returns `false` if the
+    /// tuple has already expired, been taken or had its lease cancelled.
+    fn renew_lease(&mut self, lease_id: LeaseId, duration: Duration) -> Result<bool>;
+
+    /// Removes the tuple of a lease from the store. Returns the tuple if it had not expired or
+    /// been taken.
+    fn cancel_lease(&mut self, lease_id: LeaseId) -> Result<Option<Tuple>>;
+
+    /// Removes all tuples with an expired lease from the store, returning the removed tuples.
+    fn purge_expired(&mut self) -> Result<Vec<Tuple>>;
+}
+
+/// Pairs a tuple returned by a store with the bindings of the query tuple it matched.
+fn with_bindings(query_tuple: &QueryTuple, tuple: Tuple) -> (Tuple, Bindings) {
+    let bindings = query_tuple.bind(&tuple).unwrap_or_default();
+    (tuple, bindings)
 }
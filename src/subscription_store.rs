@@ -0,0 +1,457 @@
+use crate::lease::LeaseId;
+use crate::query_tuple::QueryTuple;
+use crate::result::Result;
+use crate::store::Store;
+use crate::tuple::Tuple;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// A [SubscriptionStore] is a Tuple store wrapper that notifies subscribers when a tuple
+/// matching their query tuple is written to, or optionally taken from, the internal store.
+///
+/// Subscriptions are managed through a [Subscriptions] handle, which stays usable after the
+/// [SubscriptionStore] has been moved into another wrapper such as a
+/// [MutexStore](crate::mutex_store::MutexStore).
+/// ```rust
+/// use tuple_store::mutex_store::MutexStore;
+/// use tuple_store::query_tuple::QueryTuple;
+/// use tuple_store::store::Store;
+/// use tuple_store::subscription_store::{Event, Notify, SubscriptionStore};
+/// use tuple_store::tuple::Tuple;
+/// use tuple_store::vec_store::VecStore;
+///
+/// fn main() -> tuple_store::result::Result<()> {
+///   let subscription_store = SubscriptionStore::<VecStore>::default();
+///   let subscriptions = subscription_store.subscriptions();
+///   let mut store = MutexStore::new(subscription_store);
+///
+///   let query_tuple = QueryTuple::builder().string("result").any_integer().build();
+///   let (_id, receiver) = subscriptions.channel(&query_tuple, Notify::Writes)?;
+///
+///   store.write(&Tuple::builder().string("result").integer(42).build())?;
+///   if let Ok(Event::Written(tuple)) = receiver.try_recv() {
+///     println!("New result: {}", tuple);
+///   }
+///   Ok(())
+/// }
+/// ```
+pub struct SubscriptionStore<S: Store> {
+    store: S,
+    subscriptions: Subscriptions,
+}
+
+impl<S: Store> SubscriptionStore<S> {
+    /// Wraps `store`, which may already contain tuples.
+    pub fn new(store: S) -> SubscriptionStore<S> {
+        SubscriptionStore {
+            store,
+            subscriptions: Subscriptions::default(),
+        }
+    }
+
+    /// A handle to the subscriptions of this store.
+    pub fn subscriptions(&self) -> Subscriptions {
+        self.subscriptions.clone()
+    }
+}
+
+impl<S: Store> Default for SubscriptionStore<S> {
+    fn default() -> SubscriptionStore<S> {
+        SubscriptionStore::new(S::default())
+    }
+}
+
+impl<S: Store> Store for SubscriptionStore<S> {
+    fn size(&self) -> Result<usize> {
+        self.store.size()
+    }
+
+    fn write(&mut self, tuple: &Tuple) -> Result<()> {
+        self.store.write(tuple)?;
+        self.subscriptions.notify(Event::Written(tuple.clone()));
+        Ok(())
+    }
+
+    fn write_all<I>(&mut self, tuples: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Tuple>,
+    {
+        let subscriptions = &self.subscriptions;
+        self.store.write_all(
+            tuples
+                .into_iter()
+                .inspect(|tuple| subscriptions.notify(Event::Written(tuple.clone()))),
+        )
+    }
+
+    fn read_now(&self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
+        self.store.read_now(query_tuple)
+    }
+
+    fn take_now(&mut self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
+        let tuple = self.store.take_now(query_tuple)?;
+        if let Some(tuple) = &tuple {
+            self.subscriptions.notify(Event::Taken(tuple.clone()));
+        }
+        Ok(tuple)
+    }
+
+    fn take_many_now(&mut self, query_tuples: &[QueryTuple]) -> Result<Option<Vec<Tuple>>> {
+        let tuples = self.store.take_many_now(query_tuples)?;
+        for tuple in tuples.iter().flatten() {
+            self.subscriptions.notify(Event::Taken(tuple.clone()));
+        }
+        Ok(tuples)
+    }
+
+    fn read_all(&self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>> {
+        self.store.read_all(query_tuple)
+    }
+
+    fn take_all(&mut self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>> {
+        let tuples = self.store.take_all(query_tuple)?;
+        for tuple in &tuples {
+            self.subscriptions.notify(Event::Taken(tuple.clone()));
+        }
+        Ok(tuples)
+    }
+
+    fn write_with_lease(&mut self, tuple: &Tuple, duration: Duration) -> Result<LeaseId> {
+        let lease_id = self.store.write_with_lease(tuple, duration)?;
+        self.subscriptions.notify(Event::Written(tuple.clone()));
+        Ok(lease_id)
+    }
+
+    fn renew_lease(&mut self, lease_id: LeaseId, duration: Duration) -> Result<bool> {
+        self.store.renew_lease(lease_id, duration)
+    }
+
+    fn cancel_lease(&mut self, lease_id: LeaseId) -> Result<Option<Tuple>> {
+        let tuple = self.store.cancel_lease(lease_id)?;
+        if let Some(tuple) = &tuple {
+            self.subscriptions.notify(Event::Taken(tuple.clone()));
+        }
+        Ok(tuple)
+    }
+
+    fn purge_expired(&mut self) -> Result<Vec<Tuple>> {
+        let tuples = self.store.purge_expired()?;
+        for tuple in &tuples {
+            self.subscriptions.notify(Event::Expired(tuple.clone()));
+        }
+        Ok(tuples)
+    }
+}
+
+/// A change to a [SubscriptionStore] that a subscriber is notified of.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Written(Tuple),
+    Taken(Tuple),
+    /// The lease of the tuple expired and it was removed by [Store::purge_expired].
+    Expired(Tuple),
+}
+
+impl Event {
+    /// The tuple that was written, taken or removed.
+    pub fn tuple(&self) -> &Tuple {
+        match self {
+            Event::Written(tuple) | Event::Taken(tuple) | Event::Expired(tuple) => tuple,
+        }
+    }
+}
+
+/// The events a subscriber is notified of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notify {
+    /// Only [Event::Written].
+    Writes,
+    /// [Event::Written], and the removals [Event::Taken] and [Event::Expired].
+    WritesAndTakes,
+}
+
+/// Identifies a subscription so that it can be cancelled with [Subscriptions::unsubscribe].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(usize);
+
+enum Subscriber {
+    Callback(Box<dyn FnMut(&Event) + Send>),
+    Channel(Sender<Event>),
+}
+
+impl Subscriber {
+    /// Delivers `event`, returning `false` if the subscriber has gone away or its callback
+    /// panicked.
+    fn deliver(&mut self, event: &Event) -> bool {
+        match self {
+            Subscriber::Callback(callback) => {
+                panic::catch_unwind(AssertUnwindSafe(|| callback(event))).is_ok()
+            }
+            Subscriber::Channel(sender) => sender.send(event.clone()).is_ok(),
+        }
+    }
+}
+
+struct Subscription {
+    id: SubscriptionId,
+    query_tuple: QueryTuple,
+    notify: Notify,
+    /// Locked separately from the [Registry] so that events are delivered without holding it.
+    subscriber: Arc<Mutex<Subscriber>>,
+}
+
+impl Subscription {
+    fn wants(&self, event: &Event) -> bool {
+        let kind_wanted = match event {
+            Event::Written(_) => true,
+            Event::Taken(_) | Event::Expired(_) => self.notify == Notify::WritesAndTakes,
+        };
+        kind_wanted && self.query_tuple == *event.tuple()
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    subscriptions: Vec<Subscription>,
+    next_id: usize,
+}
+
+/// A shared handle to the subscriptions of a [SubscriptionStore].
+///
+/// Callbacks are called while the store is being modified, so they must not access the store
+/// themselves. They may use their [Subscriptions] handle, e.g. to unsubscribe after the first
+/// event. Use [Subscriptions::channel] to process events elsewhere. A callback that panics is
+/// unsubscribed, without affecting the store operation that notified it.
+#[derive(Clone, Default)]
+pub struct Subscriptions {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl Subscriptions {
+    /// Calls `callback` for every event matching `query_tuple`.
+    pub fn subscribe<F>(
+        &self,
+        query_tuple: &QueryTuple,
+        notify: Notify,
+        callback: F,
+    ) -> Result<SubscriptionId>
+    where
+        F: FnMut(&Event) + Send + 'static,
+    {
+        self.add(
+            query_tuple,
+            notify,
+            Subscriber::Callback(Box::new(callback)),
+        )
+    }
+
+    /// Sends every event matching `query_tuple` to the returned [Receiver]. The subscription
+    /// is cancelled when the [Receiver] is dropped.
+    pub fn channel(
+        &self,
+        query_tuple: &QueryTuple,
+        notify: Notify,
+    ) -> Result<(SubscriptionId, Receiver<Event>)> {
+        let (sender, receiver) = mpsc::channel();
+        let id = self.add(query_tuple, notify, Subscriber::Channel(sender))?;
+        Ok((id, receiver))
+    }
+
+    /// Cancels a subscription. Returns `false` if it did not exist.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        let mut registry = self.registry.lock()?;
+        let count = registry.subscriptions.len();
+        registry
+            .subscriptions
+            .retain(|subscription| subscription.id != id);
+        Ok(registry.subscriptions.len() != count)
+    }
+
+    fn add(
+        &self,
+        query_tuple: &QueryTuple,
+        notify: Notify,
+        subscriber: Subscriber,
+    ) -> Result<SubscriptionId> {
+        let mut registry = self.registry.lock()?;
+        let id = SubscriptionId(registry.next_id);
+        registry.next_id += 1;
+        registry.subscriptions.push(Subscription {
+            id,
+            query_tuple: query_tuple.clone(),
+            notify,
+            subscriber: Arc::new(Mutex::new(subscriber)),
+        });
+        Ok(id)
+    }
+
+    /// Delivers `event` to every subscription that wants it, without holding the registry lock
+    /// so that callbacks can use this handle. Subscriptions whose subscriber has gone away or
+    /// whose callback panicked are cancelled. As the store has already been modified, this never
+    /// fails.
+    fn notify(&self, event: Event) {
+        let subscribers: Vec<(SubscriptionId, Arc<Mutex<Subscriber>>)> = self
+            .lock_registry()
+            .subscriptions
+            .iter()
+            .filter(|subscription| subscription.wants(&event))
+            .map(|subscription| (subscription.id, subscription.subscriber.clone()))
+            .collect();
+        let gone: Vec<SubscriptionId> = subscribers
+            .into_iter()
+            .filter(|(_, subscriber)| {
+                !subscriber
+                    .lock()
+                    .is_ok_and(|mut subscriber| subscriber.deliver(&event))
+            })
+            .map(|(id, _)| id)
+            .collect();
+        if !gone.is_empty() {
+            self.lock_registry()
+                .subscriptions
+                .retain(|subscription| !gone.contains(&subscription.id));
+        }
+    }
+
+    fn lock_registry(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[test]
+fn test_subscription_store() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let mut store = SubscriptionStore::<VecStore>::default();
+    let subscriptions = store.subscriptions();
+
+    let integer_query_tuple = QueryTuple::builder().any_integer().build();
+    let writes = Arc::new(AtomicUsize::new(0));
+    let callback_writes = writes.clone();
+    let callback_id = subscriptions.subscribe(&integer_query_tuple, Notify::Writes, move |_| {
+        callback_writes.fetch_add(1, Ordering::SeqCst);
+    })?;
+    let (_, receiver) = subscriptions.channel(&integer_query_tuple, Notify::WritesAndTakes)?;
+
+    store.write(&Tuple::builder().integer(1).build())?;
+    store.write(&Tuple::builder().string("S").build())?;
+    store.take_now(&integer_query_tuple)?;
+
+    assert_eq!(1, writes.load(Ordering::SeqCst));
+    match receiver.try_recv() {
+        Ok(Event::Written(tuple)) => assert_eq!(integer_query_tuple, tuple),
+        event => panic!("Expected write, got {:?}", event),
+    }
+    match receiver.try_recv() {
+        Ok(Event::Taken(tuple)) => assert_eq!(integer_query_tuple, tuple),
+        event => panic!("Expected take, got {:?}", event),
+    }
+    assert!(receiver.try_recv().is_err());
+
+    assert!(subscriptions.unsubscribe(callback_id)?);
+    assert!(!subscriptions.unsubscribe(callback_id)?);
+    drop(receiver);
+    store.write(&Tuple::builder().integer(2).build())?;
+    assert_eq!(1, writes.load(Ordering::SeqCst));
+    assert!(store
+        .subscriptions
+        .registry
+        .lock()?
+        .subscriptions
+        .is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_subscription_store_panicking_callback() -> Result<()> {
+    use crate::vec_store::VecStore;
+
+    let mut store = SubscriptionStore::<VecStore>::default();
+    let subscriptions = store.subscriptions();
+    let panic_id = subscriptions.subscribe(
+        &QueryTuple::builder().integer(1).build(),
+        Notify::Writes,
+        |_| panic!("Callback panic"),
+    )?;
+    let (_, receiver) =
+        subscriptions.channel(&QueryTuple::builder().any_integer().build(), Notify::Writes)?;
+
+    store.write(&Tuple::builder().integer(1).build())?;
+    store.write(&Tuple::builder().integer(2).build())?;
+    assert_eq!(2, store.size()?);
+    assert_eq!(2, receiver.try_iter().count());
+    assert!(!subscriptions.unsubscribe(panic_id)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_subscription_store_one_shot_callback() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::OnceLock;
+
+    let mut store = SubscriptionStore::<VecStore>::default();
+    let subscriptions = store.subscriptions();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let unsubscribed = Arc::new(AtomicBool::new(false));
+    let one_shot_id = Arc::new(OnceLock::new());
+
+    let callback_subscriptions = subscriptions.clone();
+    let callback_calls = calls.clone();
+    let callback_unsubscribed = unsubscribed.clone();
+    let callback_id = one_shot_id.clone();
+    let id = subscriptions.subscribe(
+        &QueryTuple::builder().any_integer().build(),
+        Notify::Writes,
+        move |_| {
+            callback_calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(id) = callback_id.get() {
+                let result = callback_subscriptions.unsubscribe(*id);
+                callback_unsubscribed.store(matches!(result, Ok(true)), Ordering::SeqCst);
+            }
+        },
+    )?;
+    one_shot_id.set(id).unwrap();
+
+    store.write(&Tuple::builder().integer(1).build())?;
+    store.write(&Tuple::builder().integer(2).build())?;
+    assert_eq!(1, calls.load(Ordering::SeqCst));
+    assert!(unsubscribed.load(Ordering::SeqCst));
+    assert!(!subscriptions.unsubscribe(id)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_subscription_store_expired() -> Result<()> {
+    use crate::vec_store::VecStore;
+    use std::thread;
+
+    let mut store = SubscriptionStore::<VecStore>::default();
+    let subscriptions = store.subscriptions();
+    let heartbeat_query_tuple = QueryTuple::builder().string("heartbeat").build();
+    let (_, writes) = subscriptions.channel(&heartbeat_query_tuple, Notify::Writes)?;
+    let (_, removals) = subscriptions.channel(&heartbeat_query_tuple, Notify::WritesAndTakes)?;
+
+    store.write_with_lease(
+        &Tuple::builder().string("heartbeat").build(),
+        Duration::from_millis(10),
+    )?;
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(1, store.purge_expired()?.len());
+
+    assert!(matches!(writes.try_recv(), Ok(Event::Written(_))));
+    assert!(writes.try_recv().is_err());
+    assert!(matches!(removals.try_recv(), Ok(Event::Written(_))));
+    match removals.try_recv() {
+        Ok(Event::Expired(tuple)) => assert_eq!(heartbeat_query_tuple, tuple),
+        event => panic!("Expected expiry, got {:?}", event),
+    }
+
+    Ok(())
+}
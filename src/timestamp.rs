@@ -0,0 +1,304 @@
+use crate::error::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const NANOSECONDS_PER_SECOND: u32 = 1_000_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A UTC instant with nanosecond precision, stored as the time elapsed since the Unix epoch.
+///
+/// Timestamps are written in RFC 3339 form, e.g. `2021-03-01T12:30:00.5Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Timestamp {
+    seconds: i64,
+    nanoseconds: u32,
+}
+
+impl Timestamp {
+    /// The latest representable instant.
+    pub const MAX: Timestamp = Timestamp {
+        seconds: i64::MAX,
+        nanoseconds: NANOSECONDS_PER_SECOND - 1,
+    };
+
+    /// The current time.
+    pub fn now() -> Timestamp {
+        Timestamp::from(SystemTime::now())
+    }
+
+    /// The instant `seconds` and `nanoseconds` after the Unix epoch. Nanoseconds beyond a
+    /// second carry over into the seconds, saturating at [Timestamp::MAX].
+    pub fn from_unix(seconds: i64, nanoseconds: u32) -> Timestamp {
+        match seconds.checked_add((nanoseconds / NANOSECONDS_PER_SECOND) as i64) {
+            Some(seconds) => Timestamp {
+                seconds,
+                nanoseconds: nanoseconds % NANOSECONDS_PER_SECOND,
+            },
+            None => Timestamp::MAX,
+        }
+    }
+
+    /// The whole seconds since the Unix epoch, negative for instants before it.
+    pub fn unix_seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// The nanoseconds past [Timestamp::unix_seconds].
+    pub fn subsec_nanos(&self) -> u32 {
+        self.nanoseconds
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(system_time: SystemTime) -> Timestamp {
+        match system_time.duration_since(UNIX_EPOCH) {
+            Ok(since) => Timestamp::from_unix(since.as_secs() as i64, since.subsec_nanos()),
+            Err(error) => {
+                let before = error.duration();
+                let seconds = 0i64.saturating_sub_unsigned(before.as_secs());
+                match before.subsec_nanos() {
+                    0 => Timestamp::from_unix(seconds, 0),
+                    nanoseconds => Timestamp::from_unix(
+                        seconds.saturating_sub(1),
+                        NANOSECONDS_PER_SECOND - nanoseconds,
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Fails with [Error::TimestampOutOfRange] if the platform's [SystemTime] cannot represent the
+/// timestamp.
+impl TryFrom<Timestamp> for SystemTime {
+    type Error = Error;
+
+    fn try_from(timestamp: Timestamp) -> Result<SystemTime, Error> {
+        let whole_seconds = if timestamp.seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(timestamp.seconds as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(timestamp.seconds.unsigned_abs()))
+        };
+        whole_seconds
+            .and_then(|system_time| {
+                system_time.checked_add(Duration::from_nanos(timestamp.nanoseconds as u64))
+            })
+            .ok_or(Error::TimestampOutOfRange(timestamp))
+    }
+}
+
+/// Deserialized timestamps are normalised like [Timestamp::from_unix], so that equal instants
+/// compare equal.
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Timestamp, D::Error> {
+        let unix = UnixTimestamp::deserialize(deserializer)?;
+        Ok(Timestamp::from_unix(unix.seconds, unix.nanoseconds))
+    }
+}
+
+/// The serialized fields of a [Timestamp], which may not be normalised.
+#[derive(Deserialize)]
+#[serde(rename = "Timestamp")]
+struct UnixTimestamp {
+    seconds: i64,
+    nanoseconds: u32,
+}
+
+/// The proleptic Gregorian `(year, month, day)` of the day `days` after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// The number of days from 1970-01-01 to the proleptic Gregorian date, the inverse of
+/// [civil_from_days]. `None` if it overflows an [i64].
+fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(146_097)?.checked_add(day_of_era - 719_468)
+}
+
+/// Parses the RFC 3339 form written by [Timestamp]'s [std::fmt::Display] implementation, in UTC
+/// (`Z`) with up to nine fractional digits, e.g. `2021-03-01T12:30:00.5Z`.
+impl FromStr for Timestamp {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Timestamp, Error> {
+        parse_timestamp(text)
+            .ok_or_else(|| Error::InvalidSyntax(format!("invalid timestamp {}", text)))
+    }
+}
+
+fn parse_timestamp(text: &str) -> Option<Timestamp> {
+    let (date, time) = text.strip_suffix('Z')?.split_once('T')?;
+    let (year_month, day) = date.rsplit_once('-')?;
+    let (year, month) = year_month.rsplit_once('-')?;
+    let (year, month, day) = (year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+    let days = days_from_civil(year, month, day)?;
+
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (time, None),
+    };
+    let mut fields = time.split(':').map(|field| field.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (fields.next()??, fields.next()??, fields.next()??);
+    if fields.next().is_some()
+        || !(0..24).contains(&hours)
+        || !(0..60).contains(&minutes)
+        || !(0..60).contains(&seconds)
+    {
+        return None;
+    }
+    let nanoseconds = match fraction {
+        Some(fraction) => parse_nanoseconds(fraction)?,
+        None => 0,
+    };
+
+    // Checking the date after the seconds fit an i64 keeps civil_from_days from overflowing.
+    let seconds = days
+        .checked_mul(SECONDS_PER_DAY)?
+        .checked_add(hours * 3_600 + minutes * 60 + seconds)?;
+    if civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+    Some(Timestamp {
+        seconds,
+        nanoseconds,
+    })
+}
+
+/// Parses between one and nine fractional digits of a second into nanoseconds, so `5` is
+/// 500000000.
+pub(crate) fn parse_nanoseconds(fraction: &str) -> Option<u32> {
+    if fraction.is_empty()
+        || fraction.len() > 9
+        || !fraction.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+    format!("{:0<9}", fraction).parse().ok()
+}
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        let days = self.seconds.div_euclid(SECONDS_PER_DAY);
+        let second_of_day = self.seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        write!(
+            formatter,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            second_of_day / 3_600,
+            second_of_day % 3_600 / 60,
+            second_of_day % 60
+        )?;
+        if self.nanoseconds > 0 {
+            let fraction = format!("{:09}", self.nanoseconds);
+            write!(formatter, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        write!(formatter, "Z")
+    }
+}
+
+#[test]
+fn test_timestamp() {
+    assert_eq!(
+        "1970-01-01T00:00:00Z",
+        format!("{}", Timestamp::from_unix(0, 0))
+    );
+    assert_eq!(
+        "2021-03-01T12:30:00.5Z",
+        format!("{}", Timestamp::from_unix(1_614_601_800, 500_000_000))
+    );
+    assert_eq!(
+        "1969-12-31T23:59:59.000000001Z",
+        format!("{}", Timestamp::from_unix(-1, 1))
+    );
+    assert_eq!(
+        "2000-02-29T00:00:00Z",
+        format!("{}", Timestamp::from_unix(951_782_400, 0))
+    );
+    assert_eq!(
+        Timestamp::from_unix(1, 0),
+        Timestamp::from_unix(0, 1_000_000_000)
+    );
+    assert!(Timestamp::from_unix(-1, 999_999_999) < Timestamp::from_unix(0, 0));
+
+    for timestamp in [
+        Timestamp::from_unix(0, 0),
+        Timestamp::from_unix(1_614_601_800, 500_000_000),
+        Timestamp::from_unix(-1, 1),
+        Timestamp::from_unix(951_782_400, 0),
+        Timestamp::from_unix(-62_135_596_800, 0),
+    ] {
+        assert_eq!(
+            timestamp,
+            format!("{}", timestamp).parse::<Timestamp>().unwrap()
+        );
+    }
+    assert!("2021-02-29T00:00:00Z".parse::<Timestamp>().is_err());
+    assert!("2021-03-01T24:00:00Z".parse::<Timestamp>().is_err());
+    assert!("2021-03-01T12:30:00".parse::<Timestamp>().is_err());
+    assert!("2021-03-01T12:30:00.Z".parse::<Timestamp>().is_err());
+
+    let before_epoch = UNIX_EPOCH - Duration::from_millis(1_500);
+    assert_eq!(
+        Timestamp::from_unix(-2, 500_000_000),
+        Timestamp::from(before_epoch)
+    );
+    assert_eq!(
+        before_epoch,
+        SystemTime::try_from(Timestamp::from(before_epoch)).unwrap()
+    );
+}
+
+#[test]
+fn test_timestamp_range() {
+    use serde::de::value::{Error as DeError, SeqDeserializer};
+
+    assert_eq!(
+        Timestamp::MAX,
+        Timestamp::from_unix(i64::MAX, 1_500_000_000)
+    );
+    assert_eq!(
+        Timestamp::from_unix(i64::MAX, 999_999_999),
+        Timestamp::from_unix(i64::MAX - 1, 1_999_999_999)
+    );
+    for timestamp in [Timestamp::MAX, Timestamp::from_unix(i64::MIN, 0)] {
+        if let Ok(system_time) = SystemTime::try_from(timestamp) {
+            assert_eq!(timestamp, Timestamp::from(system_time));
+        }
+    }
+
+    let deserializer = SeqDeserializer::<_, DeError>::new(vec![1u64, 1_500_000_000].into_iter());
+    assert_eq!(
+        Timestamp::from_unix(2, 500_000_000),
+        Timestamp::deserialize(deserializer).unwrap()
+    );
+}
@@ -1,6 +1,10 @@
 use crate::query_tuple::QueryTupleBuilder;
+use crate::timestamp::Timestamp;
+use crate::types::Decimal;
 use crate::types::Types;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Tuple {
@@ -8,6 +12,10 @@ pub struct Tuple {
 }
 
 impl Tuple {
+    pub(crate) fn new(tuple: Vec<Types>) -> Tuple {
+        Tuple { tuple }
+    }
+
     /// Returns a [TupleBuilder]
     pub fn builder() -> TupleBuilder {
         TupleBuilder::default()
@@ -27,6 +35,11 @@ impl Tuple {
     pub fn is_empty(&self) -> bool {
         self.tuple.is_empty()
     }
+
+    /// An iterator over the elements of the tuple.
+    pub fn iter(&self) -> std::slice::Iter<'_, Types> {
+        self.tuple.iter()
+    }
 }
 
 impl std::fmt::Display for Tuple {
@@ -77,6 +90,57 @@ impl TupleBuilder {
         self.tuple.push(Types::String(String::from(string)));
         self
     }
+
+    pub fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.tuple.push(Types::Bytes(bytes.to_vec()));
+        self
+    }
+
+    pub fn tuple(mut self, tuple: &Tuple) -> Self {
+        self.tuple.push(Types::Tuple(tuple.clone()));
+        self
+    }
+
+    pub fn list(mut self, list: &[Types]) -> Self {
+        self.tuple.push(Types::List(list.to_vec()));
+        self
+    }
+
+    pub fn map(mut self, map: &BTreeMap<String, Types>) -> Self {
+        self.tuple.push(Types::Map(map.clone()));
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.tuple.push(Types::Timestamp(timestamp));
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.tuple.push(Types::Duration(duration));
+        self
+    }
+
+    pub fn unsigned_integer(mut self, integer: u64) -> Self {
+        self.tuple.push(Types::UnsignedInteger(integer));
+        self
+    }
+
+    pub fn integer_128(mut self, integer: i128) -> Self {
+        self.tuple.push(Types::Integer128(integer));
+        self
+    }
+
+    pub fn decimal(mut self, decimal: Decimal) -> Self {
+        self.tuple.push(Types::Decimal(decimal));
+        self
+    }
+
+    /// Adds a [Types::Null] element, representing a missing value.
+    pub fn null(mut self) -> Self {
+        self.tuple.push(Types::Null);
+        self
+    }
 }
 
 impl PartialEq for Tuple {
@@ -132,3 +196,553 @@ fn test_builder() {
         .build();
     assert_eq!(query_tuple, tuple)
 }
+
+#[test]
+fn test_bytes_builder() {
+    let tuple = Tuple::builder().string("blob").bytes(&[1, 2]).build();
+    assert_eq!(2, tuple.len());
+
+    let query_tuple = Tuple::query().string("blob").bytes(&[1, 2]).build();
+    assert_eq!(query_tuple, tuple);
+    let query_tuple = Tuple::query().string("blob").bytes(&[1]).build();
+    assert_ne!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query().any_string().any_bytes().build();
+    assert_eq!(query_tuple, tuple);
+    let query_tuple = Tuple::query().any_string().any_string().build();
+    assert_ne!(query_tuple, tuple);
+}
+
+#[test]
+fn test_nested_builder() {
+    use crate::query_tuple::QueryTuple;
+
+    let address = Tuple::builder()
+        .string("addr")
+        .string("street")
+        .integer(7)
+        .build();
+    let order = Tuple::builder()
+        .string("order")
+        .integer(42)
+        .tuple(&address)
+        .build();
+    assert_eq!(3, order.len());
+    assert_eq!(
+        "(\"order\", 42, (\"addr\", \"street\", 7))",
+        format!("{}", order)
+    );
+
+    let query_tuple = Tuple::query()
+        .string("order")
+        .any_integer()
+        .tuple(
+            Tuple::query()
+                .string("addr")
+                .any_string()
+                .any_integer()
+                .build(),
+        )
+        .build();
+    assert_eq!(query_tuple, order);
+    assert_eq!(QueryTuple::from(&order), order);
+
+    let query_tuple = Tuple::query()
+        .string("order")
+        .any_integer()
+        .any_tuple()
+        .build();
+    assert_eq!(query_tuple, order);
+}
+
+#[test]
+fn test_collection_builder() {
+    use crate::types::QueryTypes;
+
+    let features = [Types::Float(0.5), Types::Float(1.5)];
+    let metadata = BTreeMap::from([(String::from("source"), Types::String(String::from("cam")))]);
+    let tuple = Tuple::builder().list(&features).map(&metadata).build();
+
+    let query_tuple = Tuple::query().list(&features).map(&metadata).build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .list_contains(QueryTypes::ExactFloat(1.5))
+        .map_entry("source", QueryTypes::AnyString)
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query().list_length(..=2).map_length(1..).build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query().list_length(3..).any_map().build();
+    assert_ne!(query_tuple, tuple);
+}
+
+#[test]
+fn test_time_builder() {
+    let created = Timestamp::from_unix(1_614_601_800, 0);
+    let tuple = Tuple::builder()
+        .string("job")
+        .timestamp(created)
+        .duration(Duration::from_secs(30))
+        .build();
+
+    let query_tuple = Tuple::query()
+        .string("job")
+        .timestamp(created)
+        .duration(Duration::from_secs(30))
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("job")
+        .any_timestamp()
+        .any_duration()
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("job")
+        .timestamp_range(..Timestamp::from_unix(1_614_601_801, 0))
+        .duration_range(Duration::from_secs(10)..=Duration::from_secs(30))
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("job")
+        .timestamp_range(Timestamp::from_unix(1_614_601_801, 0)..)
+        .any_duration()
+        .build();
+    assert_ne!(query_tuple, tuple);
+}
+
+#[test]
+fn test_null_builder() {
+    use crate::types::QueryTypes;
+
+    let tuple = Tuple::builder().string("reading").null().build();
+    let other_tuple = Tuple::builder().string("reading").integer(5).build();
+    assert_eq!("(\"reading\", null)", format!("{}", tuple));
+
+    let query_tuple = Tuple::query().string("reading").null().build();
+    assert_eq!(query_tuple, tuple);
+    assert_ne!(query_tuple, other_tuple);
+
+    let query_tuple = Tuple::query()
+        .string("reading")
+        .optional(QueryTypes::AnyInteger)
+        .build();
+    assert_eq!(query_tuple, tuple);
+    assert_eq!(query_tuple, other_tuple);
+}
+
+#[test]
+fn test_wide_numeric_builder() {
+    let tuple = Tuple::builder()
+        .string("invoice")
+        .unsigned_integer(7)
+        .integer_128(-1)
+        .decimal(Decimal::new(19_99, 2))
+        .build();
+    assert_eq!("(\"invoice\", 7u64, -1i128, 19.99d)", format!("{}", tuple));
+
+    let query_tuple = Tuple::query()
+        .string("invoice")
+        .unsigned_integer(7)
+        .integer_128(-1)
+        .decimal(Decimal::new(19_990, 3))
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("invoice")
+        .unsigned_integer_range(5..10)
+        .integer_128_range(..0)
+        .decimal_range(Decimal::new(10, 0)..=Decimal::new(20, 0))
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("invoice")
+        .integer(7)
+        .any_integer_128()
+        .any_decimal()
+        .build();
+    assert_ne!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("invoice")
+        .any_unsigned_integer()
+        .any_integer_128()
+        .any_decimal()
+        .build();
+    assert_eq!(query_tuple, tuple);
+}
+
+#[test]
+fn test_range_builder() {
+    let tuple = Tuple::builder()
+        .string("job")
+        .integer(7)
+        .float(0.25)
+        .build();
+
+    let query_tuple = Tuple::query()
+        .string_range("job".."jobz")
+        .integer_range(5..=10)
+        .float_range(0.0..1.0)
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string_range(.."job")
+        .integer_range(5..=10)
+        .float_range(0.0..1.0)
+        .build();
+    assert_ne!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .any_string()
+        .integer_range(8..)
+        .float_range(..)
+        .build();
+    assert_ne!(query_tuple, tuple);
+}
+
+#[test]
+fn test_string_pattern_builder() {
+    let tuple = Tuple::builder()
+        .string("sensor/room1/temp")
+        .float(21.5)
+        .build();
+
+    assert_eq!(
+        Tuple::query()
+            .string_prefix("sensor/room1/")
+            .any_float()
+            .build(),
+        tuple
+    );
+    assert_eq!(
+        Tuple::query().string_suffix("/temp").any_float().build(),
+        tuple
+    );
+    assert_eq!(
+        Tuple::query().string_contains("room1").any_float().build(),
+        tuple
+    );
+    assert_eq!(
+        Tuple::query()
+            .string_glob("sensor/*/temp")
+            .any_float()
+            .build(),
+        tuple
+    );
+    assert_ne!(
+        Tuple::query()
+            .string_prefix("sensor/room2/")
+            .any_float()
+            .build(),
+        tuple
+    );
+}
+
+#[test]
+fn test_regex_builder() -> crate::result::Result<()> {
+    let tuple = Tuple::builder().string("log").string("ERR-0042").build();
+
+    let query_tuple = Tuple::query()
+        .string("log")
+        .string_regex("^ERR-[0-9]{4}$")?
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query().string("log").string_regex("^WARN-")?.build();
+    assert_ne!(query_tuple, tuple);
+
+    assert!(Tuple::query().string_regex("(").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_float_builder() {
+    let tuple = Tuple::builder()
+        .string("energy")
+        .float(0.1 + 0.2)
+        .float(f64::NAN)
+        .build();
+    assert_eq!(tuple, tuple.clone());
+
+    let query_tuple = Tuple::query()
+        .string("energy")
+        .float_approx(0.3, 1e-9, 1e-9)
+        .float_nan()
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("energy")
+        .float(0.3)
+        .float_nan()
+        .build();
+    assert_ne!(query_tuple, tuple);
+}
+
+#[test]
+fn test_rest_builder() {
+    use crate::types::QueryTypes;
+
+    let event = Tuple::builder().string("event").build();
+    let event_with_payload = Tuple::builder()
+        .string("event")
+        .integer(1)
+        .integer(2)
+        .build();
+    let event_with_mixed_payload = Tuple::builder()
+        .string("event")
+        .integer(1)
+        .string("S")
+        .build();
+
+    let query_tuple = Tuple::query().string("event").rest().build();
+    assert_eq!(1, query_tuple.len());
+    assert_eq!(query_tuple, event);
+    assert_eq!(query_tuple, event_with_payload);
+    assert_eq!(query_tuple, event_with_mixed_payload);
+    assert_ne!(query_tuple, Tuple::builder().string("other").build());
+
+    let query_tuple = Tuple::query()
+        .string("event")
+        .rest_of(QueryTypes::AnyInteger)
+        .build();
+    assert_eq!(query_tuple, event);
+    assert_eq!(query_tuple, event_with_payload);
+    assert_ne!(query_tuple, event_with_mixed_payload);
+
+    let query_tuple = Tuple::query().string("event").any_integer().rest().build();
+    assert_ne!(query_tuple, event);
+    assert_eq!(query_tuple, event_with_payload);
+
+    let query_tuple = Tuple::query().string("event").build();
+    assert_eq!(query_tuple, event);
+    assert_ne!(query_tuple, event_with_payload);
+}
+
+#[test]
+fn test_variable_builder() {
+    use crate::types::QueryTypes;
+
+    let query_tuple = Tuple::query()
+        .variable("x")
+        .string("edge")
+        .variable("x")
+        .build();
+    let self_loop = Tuple::builder()
+        .string("a")
+        .string("edge")
+        .string("a")
+        .build();
+    let edge = Tuple::builder()
+        .string("a")
+        .string("edge")
+        .string("b")
+        .build();
+
+    assert_eq!(query_tuple, self_loop);
+    assert_ne!(query_tuple, edge);
+    let bindings = query_tuple.bind(&self_loop).unwrap();
+    assert_eq!(1, bindings.len());
+    assert_eq!(Some(&Types::String(String::from("a"))), bindings.get("x"));
+    assert!(query_tuple.bind(&edge).is_none());
+
+    let query_tuple = Tuple::query()
+        .variable("from")
+        .string("edge")
+        .variable("to")
+        .build();
+    let bindings = query_tuple.bind(&edge).unwrap();
+    assert_eq!(
+        Some(&Types::String(String::from("a"))),
+        bindings.get("from")
+    );
+    assert_eq!(Some(&Types::String(String::from("b"))), bindings.get("to"));
+
+    let nested = Tuple::builder()
+        .integer(1)
+        .tuple(&Tuple::builder().integer(1).build())
+        .build();
+    let query_tuple = Tuple::query()
+        .variable("x")
+        .tuple(Tuple::query().variable("x").build())
+        .build();
+    assert_eq!(query_tuple, nested);
+    let query_tuple = Tuple::query()
+        .variable("x")
+        .tuple(Tuple::query().integer(2).build())
+        .build();
+    assert_ne!(query_tuple, nested);
+
+    let query_tuple = Tuple::query()
+        .variable("x")
+        .rest_of(QueryTypes::Variable(String::from("x")))
+        .build();
+    assert_eq!(
+        query_tuple,
+        Tuple::builder().integer(1).integer(1).integer(1).build()
+    );
+    assert_ne!(
+        query_tuple,
+        Tuple::builder().integer(1).integer(1).integer(2).build()
+    );
+
+    let joined = Tuple::builder()
+        .list(&[Types::Integer(1), Types::Integer(2)])
+        .integer(2)
+        .build();
+    let query_tuple = Tuple::query()
+        .list_contains(QueryTypes::Variable(String::from("x")))
+        .variable("x")
+        .build();
+    assert_eq!(query_tuple, joined);
+    assert_eq!(
+        Some(&Types::Integer(2)),
+        query_tuple.bind(&joined).unwrap().get("x")
+    );
+    assert_ne!(
+        query_tuple,
+        Tuple::builder()
+            .list(&[Types::Integer(1), Types::Integer(2)])
+            .integer(3)
+            .build()
+    );
+    let query_tuple = Tuple::query()
+        .tuple(
+            Tuple::query()
+                .list_contains(QueryTypes::Variable(String::from("x")))
+                .build(),
+        )
+        .variable("x")
+        .build();
+    assert_eq!(
+        query_tuple,
+        Tuple::builder()
+            .tuple(
+                &Tuple::builder()
+                    .list(&[Types::Integer(1), Types::Integer(2)])
+                    .build()
+            )
+            .integer(2)
+            .build()
+    );
+
+    let query_tuple = Tuple::query()
+        .one_of(vec![
+            QueryTypes::Variable(String::from("x")),
+            QueryTypes::Any,
+        ])
+        .variable("x")
+        .build();
+    let bindings = query_tuple
+        .bind(&Tuple::builder().integer(1).integer(2).build())
+        .unwrap();
+    assert_eq!(Some(&Types::Integer(2)), bindings.get("x"));
+
+    let query_tuple = Tuple::query()
+        .list_contains(QueryTypes::Variable(String::from("x")))
+        .rest_of(QueryTypes::Variable(String::from("x")))
+        .build();
+    let mut builder = Tuple::builder().list(&[Types::Integer(1), Types::Integer(2)]);
+    for _ in 0..100_000 {
+        builder = builder.integer(2);
+    }
+    assert_eq!(query_tuple, builder.build());
+}
+
+#[test]
+fn test_combinator_builder() {
+    use crate::types::QueryTypes;
+
+    let email = Tuple::builder()
+        .string("job")
+        .string("email")
+        .integer(1)
+        .build();
+    let sms = Tuple::builder().string("job").string("sms").build();
+    let fax = Tuple::builder().string("job").string("fax").build();
+
+    let query_tuple = Tuple::query()
+        .string("job")
+        .one_of(vec![
+            QueryTypes::ExactString(String::from("email")),
+            QueryTypes::ExactString(String::from("sms")),
+        ])
+        .rest()
+        .build();
+    assert_eq!(query_tuple, email);
+    assert_eq!(query_tuple, sms);
+    assert_ne!(query_tuple, fax);
+
+    let query_tuple = Tuple::query()
+        .string("job")
+        .not(QueryTypes::ExactString(String::from("fax")))
+        .rest()
+        .build();
+    assert_eq!(query_tuple, email);
+    assert_ne!(query_tuple, fax);
+
+    let query_tuple = Tuple::query()
+        .string("job")
+        .string("email")
+        .any_integer()
+        .build()
+        .or(Tuple::query().string("job").variable("channel").build());
+    assert_eq!(query_tuple, email);
+    assert_eq!(query_tuple, fax);
+    assert!(query_tuple.bind(&email).unwrap().is_empty());
+    assert_eq!(
+        Some(&Types::String(String::from("fax"))),
+        query_tuple.bind(&fax).unwrap().get("channel")
+    );
+    assert_ne!(query_tuple, Tuple::builder().string("job").build());
+}
+
+#[test]
+fn test_predicate_builder() {
+    let tuple = Tuple::builder()
+        .string("order")
+        .integer(4)
+        .integer(4)
+        .build();
+
+    let query_tuple = Tuple::query()
+        .string("order")
+        .predicate(|id| matches!(id, Types::Integer(id) if id % 2 == 0))
+        .any_integer()
+        .build();
+    assert_eq!(query_tuple, tuple);
+
+    let query_tuple = Tuple::query()
+        .string("order")
+        .predicate(|id| matches!(id, Types::Integer(id) if id % 2 == 1))
+        .any_integer()
+        .build();
+    assert_ne!(query_tuple, tuple);
+
+    let checksum_valid = |tuple: &Tuple| tuple[1] == tuple[2];
+    let query_tuple = Tuple::query()
+        .string("order")
+        .any_integer()
+        .any_integer()
+        .tuple_predicate(checksum_valid)
+        .build();
+    assert_eq!(query_tuple, tuple);
+    assert_ne!(
+        query_tuple,
+        Tuple::builder()
+            .string("order")
+            .integer(4)
+            .integer(5)
+            .build()
+    );
+}
@@ -1,19 +1,49 @@
+use crate::pattern::Pattern;
+use crate::predicate::Predicate;
+use crate::query_tuple::{Bindings, QueryTuple};
+use crate::timestamp::Timestamp;
+use crate::tuple::Tuple;
+pub use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::{Bound, RangeBounds};
+use std::time::Duration;
 
+/// Generates [Types] with one variant per stored type, and [QueryTypes] with an exact and a
+/// wildcard variant per stored type. Types without a value, listed after the first `;`, get a
+/// single exact query variant. Query types that do not follow either pattern are listed after the
+/// second `;` and matched by [QueryTypes::satisfy_extra].
 macro_rules! tuple_types {
-    ($(($type:ty, $name:ident,$exact:ident,$any:ident)),+) => {
+    (
+        $(($type:ty, $name:ident, $exact:ident($exact_type:ty), $any:ident)),+;
+        $(($unit:ident, $unit_exact:ident, $unit_text:literal)),*;
+        $($extra:tt)*
+    ) => {
         #[derive(Debug, Serialize, Deserialize, Clone)]
         pub enum Types {
             $(
                 $name($type),
             )*
+            $(
+                $unit,
+            )*
         }
 
         impl Types {
+            /// Elements are equal if they have the same type and equal values.
+            ///
+            /// Floats are equal if they are `==`, so `0.0` and `-0.0` are equal, or if both are
+            /// `NaN`, whatever their sign and payload.
+            ///
+            /// Floats are ordered by [QueryTypes::FloatRange] with the partial order of `<`, in which
+            /// `-0.0` and `0.0` are equal and `NaN` is unordered, so never within bounds.
             fn satisfy(&self, other: &Types) -> bool {
                 match (self, other) {
                     $(
-                        (Self::$name(lhs), Self::$name(rhs)) => lhs == rhs,
+                        (Self::$name(lhs), Self::$name(rhs)) => lhs.equals(rhs),
+                    )*
+                    $(
+                        (Self::$unit, Self::$unit) => true,
                     )*
                         _ => false,
                 }
@@ -24,7 +54,10 @@ macro_rules! tuple_types {
             fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
                 match self {
                     $(
-                        Self::$name(inner_value) => write!(formatter, "{}", inner_value)?,
+                        Self::$name(inner_value) => inner_value.fmt_element(formatter)?,
+                    )*
+                    $(
+                        Self::$unit => write!(formatter, $unit_text)?,
                     )*
                 };
                 Ok(())
@@ -32,13 +65,36 @@ macro_rules! tuple_types {
         }
 
 
+        /// An element of a [QueryTuple], matching elements of a [Tuple].
+        ///
+        /// Query types only match elements of the type they were created for: numeric values of
+        /// different types never match each other, so `ExactInteger(1)` does not match
+        /// `UnsignedInteger(1)`, `Integer128(1)` or `Float(1.0)`.
         #[derive(Debug, Serialize, Deserialize, Clone)]
         pub enum QueryTypes {
             Any,
             $(
-                $exact($type),
+                $exact($exact_type),
                 $any,
             )*
+            $(
+                $unit_exact,
+            )*
+            $($extra)*
+        }
+
+        /// The query type matching exactly the value of a [Types].
+        impl From<&Types> for QueryTypes {
+            fn from(value: &Types) -> QueryTypes {
+                match value {
+                    $(
+                        Types::$name(inner_value) => QueryTypes::$exact(<$exact_type>::from(inner_value.clone())),
+                    )*
+                    $(
+                        Types::$unit => QueryTypes::$unit_exact,
+                    )*
+                }
+            }
         }
 
 
@@ -51,22 +107,400 @@ macro_rules! tuple_types {
                         (Self::$any, _) => false,
                     )*
                     $(
-                        (Self::$exact(lhs), Types::$name(rhs)) => lhs == rhs,
+                        (Self::$exact(lhs), Types::$name(rhs)) => lhs.matches_exactly(rhs),
                         (Self::$exact(_), _) => false,
                     )*
+                    $(
+                        (Self::$unit_exact, Types::$unit) => true,
+                        (Self::$unit_exact, _) => false,
+                    )*
+                    _ => self.satisfy_extra(other),
                 }
             }
         }
     };
 }
 
+/// How the value of an element is written by the [std::fmt::Display] implementation of [Types],
+/// and how two values of the same type are compared.
+trait Element: PartialEq {
+    fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result;
+
+    fn equals(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// How the value of an exact query type is compared with the value of an element.
+trait ExactElement<T> {
+    fn matches_exactly(&self, element: &T) -> bool;
+}
+
+impl<T: Element> ExactElement<T> for T {
+    fn matches_exactly(&self, element: &T) -> bool {
+        self.equals(element)
+    }
+}
+
+impl ExactElement<Tuple> for QueryTuple {
+    fn matches_exactly(&self, element: &Tuple) -> bool {
+        self == element
+    }
+}
+
+macro_rules! display_elements {
+    ($($type:ty),+) => {
+        $(
+            impl Element for $type {
+                fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(formatter, "{}", self)
+                }
+            }
+        )*
+    };
+}
+
+display_elements!(bool, i64, Tuple, Timestamp);
+
+/// Strings are written quoted, with quotes, backslashes and control characters escaped as in
+/// Rust string literals, e.g. `"say \"hi\"\n"`.
+impl Element for String {
+    fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{:?}", self)
+    }
+}
+
+/// Floats are equal if they are `==` or both `NaN`, see [Types]. They are always written with a
+/// fraction or exponent, e.g. `1.0`, so that they can be told apart from integers.
+impl Element for f64 {
+    fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{:?}", self)
+    }
+
+    fn equals(&self, other: &f64) -> bool {
+        self == other || (self.is_nan() && other.is_nan())
+    }
+}
+
+/// Numbers other than [i64] and [f64] are written with a suffix naming their type, e.g. `5u64`,
+/// `5i128` and `5.00d` for a [Decimal].
+macro_rules! display_suffixed_elements {
+    ($(($type:ty, $suffix:literal)),+) => {
+        $(
+            impl Element for $type {
+                fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    write!(formatter, "{}{}", self, $suffix)
+                }
+            }
+        )*
+    };
+}
+
+display_suffixed_elements!((u64, "u64"), (i128, "i128"), (Decimal, "d"));
+
+/// Durations are written in ISO 8601 form, e.g. `PT1.5S`.
+impl Element for Duration {
+    fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "PT{}", self.as_secs())?;
+        if self.subsec_nanos() > 0 {
+            let fraction = format!("{:09}", self.subsec_nanos());
+            write!(formatter, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        write!(formatter, "S")
+    }
+}
+
+/// Lists are written as `[1, 2, 3]`.
+impl Element for Vec<Types> {
+    fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "[")?;
+        for (index, element) in self.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, ", ")?;
+            }
+            write!(formatter, "{}", element)?;
+        }
+        write!(formatter, "]")
+    }
+}
+
+/// Maps are written as `{"key": value, ...}` in key order.
+impl Element for BTreeMap<String, Types> {
+    fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{{")?;
+        for (index, (key, value)) in self.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, ", ")?;
+            }
+            write!(formatter, "{:?}: {}", key, value)?;
+        }
+        write!(formatter, "}}")
+    }
+}
+
+/// Bytes are written as hexadecimal, e.g. `0x00ff`.
+impl Element for Vec<u8> {
+    fn fmt_element(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "0x")?;
+        self.iter()
+            .try_for_each(|byte| write!(formatter, "{:02x}", byte))
+    }
+}
+
 tuple_types![
-    (bool, Boolean, ExactBoolean, AnyBoolean),
-    (i64, Integer, ExactInteger, AnyInteger),
-    (f64, Float, ExactFloat, AnyFloat),
-    (String, String, ExactString, AnyString)
+    (bool, Boolean, ExactBoolean(bool), AnyBoolean),
+    (i64, Integer, ExactInteger(i64), AnyInteger),
+    (f64, Float, ExactFloat(f64), AnyFloat),
+    (String, String, ExactString(String), AnyString),
+    (Vec<u8>, Bytes, ExactBytes(Vec<u8>), AnyBytes),
+    (Tuple, Tuple, ExactTuple(QueryTuple), AnyTuple),
+    (Vec<Types>, List, ExactList(Vec<Types>), AnyList),
+    (BTreeMap<String, Types>, Map, ExactMap(BTreeMap<String, Types>), AnyMap),
+    (Timestamp, Timestamp, ExactTimestamp(Timestamp), AnyTimestamp),
+    (Duration, Duration, ExactDuration(Duration), AnyDuration),
+    (u64, UnsignedInteger, ExactUnsignedInteger(u64), AnyUnsignedInteger),
+    (i128, Integer128, ExactInteger128(i128), AnyInteger128),
+    (Decimal, Decimal, ExactDecimal(Decimal), AnyDecimal);
+    (Null, ExactNull, "null");
+    /// A list with at least one element matching the query type.
+    ListContains(Box<QueryTypes>),
+    /// A list whose number of elements lies within the bounds.
+    ListLength(Bound<usize>, Bound<usize>),
+    /// A map with the key, whose value matches the query type.
+    MapEntry(String, Box<QueryTypes>),
+    /// A map whose number of entries lies within the bounds.
+    MapLength(Bound<usize>, Bound<usize>),
+    /// An integer within the bounds.
+    IntegerRange(Bound<i64>, Bound<i64>),
+    /// A float within the bounds. `NaN` is never within bounds.
+    FloatRange(Bound<f64>, Bound<f64>),
+    /// A float within `abs_eps` of `value`, or within `rel_eps` times the larger magnitude of the
+    /// two, whichever is more lenient. `NaN` never matches, and an infinity only matches itself.
+    FloatApprox {
+        value: f64,
+        abs_eps: f64,
+        rel_eps: f64,
+    },
+    /// A float that is `NaN`.
+    FloatNaN,
+    /// A string within the bounds, in lexical (byte-wise) order.
+    StringRange(Bound<String>, Bound<String>),
+    /// A string starting with the prefix.
+    StringPrefix(String),
+    /// A string ending with the suffix.
+    StringSuffix(String),
+    /// A string containing the substring.
+    StringContains(String),
+    /// A string matching the glob pattern, where `*` matches any sequence of characters and `?`
+    /// matches a single character.
+    StringGlob(String),
+    /// A string in which the regular expression matches. Use `^` and `$` to match the whole
+    /// string.
+    StringRegex(Pattern),
+    /// A timestamp within the bounds.
+    TimestampRange(Bound<Timestamp>, Bound<Timestamp>),
+    /// A duration within the bounds.
+    DurationRange(Bound<Duration>, Bound<Duration>),
+    /// An unsigned integer within the bounds.
+    UnsignedIntegerRange(Bound<u64>, Bound<u64>),
+    /// A 128 bit integer within the bounds.
+    Integer128Range(Bound<i128>, Bound<i128>),
+    /// A decimal within the bounds.
+    DecimalRange(Bound<Decimal>, Bound<Decimal>),
+    /// Either [Types::Null] or a value matching the query type.
+    Optional(Box<QueryTypes>),
+    /// An element matching any of the query types.
+    OneOf(Vec<QueryTypes>),
+    /// An element not matching the query type. Variables within it are never bound.
+    Not(Box<QueryTypes>),
+    /// An element for which the predicate returns `true`. Query tuples containing a predicate
+    /// cannot be serialized.
+    Predicate(Predicate<Types>),
+    /// Any element, bound to the named variable. Every occurrence of the variable in a
+    /// [QueryTuple] must be bound to equal elements, see [QueryTuple::bind].
+    Variable(String),
 ];
 
+impl QueryTypes {
+    /// Matches the query types listed after the `;` in [tuple_types].
+    fn satisfy_extra(&self, other: &Types) -> bool {
+        match (self, other) {
+            (Self::ListContains(query_type), Types::List(list)) => {
+                list.iter().any(|element| query_type.satisfy(element))
+            }
+            (Self::ListLength(start, end), Types::List(list)) => {
+                (start.as_ref(), end.as_ref()).contains(&list.len())
+            }
+            (Self::MapEntry(key, query_type), Types::Map(map)) => {
+                map.get(key).is_some_and(|value| query_type.satisfy(value))
+            }
+            (Self::MapLength(start, end), Types::Map(map)) => {
+                (start.as_ref(), end.as_ref()).contains(&map.len())
+            }
+            (Self::IntegerRange(start, end), Types::Integer(integer)) => {
+                (start.as_ref(), end.as_ref()).contains(integer)
+            }
+            (Self::FloatRange(start, end), Types::Float(float)) => {
+                !float.is_nan() && (start.as_ref(), end.as_ref()).contains(float)
+            }
+            (
+                Self::FloatApprox {
+                    value,
+                    abs_eps,
+                    rel_eps,
+                },
+                Types::Float(float),
+            ) => {
+                let difference = (value - float).abs();
+                let tolerance = abs_eps.max(rel_eps * value.abs().max(float.abs()));
+                value == float || (difference.is_finite() && difference <= tolerance)
+            }
+            (Self::FloatNaN, Types::Float(float)) => float.is_nan(),
+            (Self::StringRange(start, end), Types::String(string)) => {
+                (start.as_ref(), end.as_ref()).contains(string)
+            }
+            (Self::StringPrefix(prefix), Types::String(string)) => string.starts_with(prefix),
+            (Self::StringSuffix(suffix), Types::String(string)) => string.ends_with(suffix),
+            (Self::StringContains(substring), Types::String(string)) => {
+                string.contains(substring.as_str())
+            }
+            (Self::StringGlob(pattern), Types::String(string)) => glob_match(pattern, string),
+            (Self::StringRegex(pattern), Types::String(string)) => pattern.is_match(string),
+            (Self::TimestampRange(start, end), Types::Timestamp(timestamp)) => {
+                (start.as_ref(), end.as_ref()).contains(timestamp)
+            }
+            (Self::DurationRange(start, end), Types::Duration(duration)) => {
+                (start.as_ref(), end.as_ref()).contains(duration)
+            }
+            (Self::UnsignedIntegerRange(start, end), Types::UnsignedInteger(integer)) => {
+                (start.as_ref(), end.as_ref()).contains(integer)
+            }
+            (Self::Integer128Range(start, end), Types::Integer128(integer)) => {
+                (start.as_ref(), end.as_ref()).contains(integer)
+            }
+            (Self::DecimalRange(start, end), Types::Decimal(decimal)) => {
+                (start.as_ref(), end.as_ref()).contains(decimal)
+            }
+            (Self::Optional(_), Types::Null) => true,
+            (Self::Optional(query_type), _) => query_type.satisfy(other),
+            (Self::OneOf(query_types), _) => query_types
+                .iter()
+                .any(|query_type| query_type.satisfy(other)),
+            (Self::Not(query_type), _) => !query_type.satisfy(other),
+            (Self::Predicate(predicate), _) => predicate.test(other),
+            (Self::Variable(_), _) => true,
+            _ => false,
+        }
+    }
+
+    /// Matches `other` like [QueryTypes::satisfy], binding variables in `bindings`. A variable
+    /// that is already bound only matches an element equal to its value.
+    pub(crate) fn unify(&self, other: &Types, bindings: &mut Bindings) -> bool {
+        self.unify_then(other, bindings, &mut |_| true)
+    }
+
+    /// Matches `other` like [QueryTypes::unify], then calls `then` with the extended bindings.
+    /// A list element and [QueryTypes::OneOf] backtrack: every list element and every query type
+    /// that unifies is tried in turn until `then` returns `true`, so `(list_contains(?x), ?x)`
+    /// matches `([1, 2], 2)`. `bindings` is only changed if `true` is returned.
+    pub(crate) fn unify_then(
+        &self,
+        other: &Types,
+        bindings: &mut Bindings,
+        then: &mut dyn FnMut(&mut Bindings) -> bool,
+    ) -> bool {
+        match (self, other) {
+            (Self::Variable(name), _) => match bindings.get(name) {
+                Some(value) => value.satisfy(other) && then(bindings),
+                None => bindings.attempt(|bindings| {
+                    bindings.insert(name, other.clone());
+                    then(bindings)
+                }),
+            },
+            (Self::ExactTuple(query_tuple), Types::Tuple(tuple)) => {
+                query_tuple.unify_then(tuple, bindings, then)
+            }
+            (Self::ListContains(_) | Self::OneOf(_), _) if !self.binds_variables() => {
+                self.satisfy(other) && then(bindings)
+            }
+            (Self::ListContains(query_type), Types::List(list)) => list
+                .iter()
+                .any(|element| query_type.unify_then(element, bindings, then)),
+            (Self::MapEntry(key, query_type), Types::Map(map)) => map
+                .get(key)
+                .is_some_and(|value| query_type.unify_then(value, bindings, then)),
+            (Self::Optional(_), Types::Null) => then(bindings),
+            (Self::Optional(query_type), _) => query_type.unify_then(other, bindings, then),
+            (Self::OneOf(query_types), _) => query_types
+                .iter()
+                .any(|query_type| query_type.unify_then(other, bindings, then)),
+            (Self::Not(query_type), _) => {
+                !query_type.unify(other, &mut bindings.clone()) && then(bindings)
+            }
+            _ => self.satisfy(other) && then(bindings),
+        }
+    }
+
+    /// `false` if unifying may have to backtrack over several ways of matching an element, which
+    /// only matters if they bind variables differently.
+    pub(crate) fn is_deterministic(&self) -> bool {
+        match self {
+            Self::ListContains(_) | Self::OneOf(_) => !self.binds_variables(),
+            Self::ExactTuple(query_tuple) => query_tuple.is_deterministic(),
+            Self::MapEntry(_, query_type) | Self::Optional(query_type) => {
+                query_type.is_deterministic()
+            }
+            _ => true,
+        }
+    }
+
+    /// `true` if unifying may bind a variable. Variables within [QueryTypes::Not] never do.
+    pub(crate) fn binds_variables(&self) -> bool {
+        match self {
+            Self::Variable(_) => true,
+            Self::ExactTuple(query_tuple) => query_tuple.binds_variables(),
+            Self::ListContains(query_type)
+            | Self::MapEntry(_, query_type)
+            | Self::Optional(query_type) => query_type.binds_variables(),
+            Self::OneOf(query_types) => query_types.iter().any(QueryTypes::binds_variables),
+            _ => false,
+        }
+    }
+}
+
+/// Matches `string` against a glob `pattern` supporting `*` and `?`. On a mismatch after a `*`,
+/// the `*` is retried with one more character, which avoids exponential backtracking.
+fn glob_match(pattern: &str, string: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let string: Vec<char> = string.chars().collect();
+    let (mut pattern_index, mut string_index) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while string_index < string.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                star = Some((pattern_index, string_index));
+                pattern_index += 1;
+            }
+            Some(&character) if character == '?' || character == string[string_index] => {
+                pattern_index += 1;
+                string_index += 1;
+            }
+            _ => match star {
+                Some((star_pattern_index, star_string_index)) => {
+                    pattern_index = star_pattern_index + 1;
+                    string_index = star_string_index + 1;
+                    star = Some((star_pattern_index, string_index));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[pattern_index..]
+        .iter()
+        .all(|&character| character == '*')
+}
+
 impl PartialEq<Types> for QueryTypes {
     fn eq(&self, other: &Types) -> bool {
         self.satisfy(other)
@@ -133,3 +567,418 @@ fn test_query_compare() {
     assert_eq!(QueryTypes::ExactFloat(1.0), float);
     assert_eq!(QueryTypes::ExactBoolean(true), boolean);
 }
+
+#[test]
+fn test_bytes_compare() {
+    let by1 = Types::Bytes(vec![0, 255]);
+    let by1_copy = Types::Bytes(vec![0, 255]);
+    let by2 = Types::Bytes(vec![1]);
+    let string = Types::String(String::from("S1"));
+
+    assert_eq!(by1, by1_copy);
+    assert_ne!(by1, by2);
+    assert_ne!(by1, string);
+    assert_eq!("0x00ff", format!("{}", by1));
+
+    assert_eq!(QueryTypes::Any, by1);
+    assert_eq!(QueryTypes::AnyBytes, by1);
+    assert_ne!(QueryTypes::AnyBytes, string);
+    assert_eq!(QueryTypes::ExactBytes(vec![0, 255]), by1);
+    assert_ne!(QueryTypes::ExactBytes(vec![0]), by1);
+}
+
+#[test]
+fn test_nested_compare() {
+    let address = Tuple::builder()
+        .string("addr")
+        .string("street")
+        .integer(7)
+        .build();
+    let tuple = Types::Tuple(address.clone());
+
+    assert_eq!(tuple, Types::Tuple(address.clone()));
+    assert_ne!(tuple, Types::Tuple(Tuple::builder().string("addr").build()));
+    assert_eq!("(\"addr\", \"street\", 7)", format!("{}", tuple));
+
+    assert_eq!(QueryTypes::AnyTuple, tuple);
+    assert_ne!(QueryTypes::AnyTuple, Types::Integer(7));
+    assert_eq!(QueryTypes::from(&tuple), tuple);
+    assert_eq!(
+        QueryTypes::ExactTuple(
+            QueryTuple::builder()
+                .string("addr")
+                .any_string()
+                .any_integer()
+                .build()
+        ),
+        tuple
+    );
+    assert_ne!(
+        QueryTypes::ExactTuple(QueryTuple::builder().string("addr").any().build()),
+        tuple
+    );
+}
+
+#[test]
+fn test_collection_compare() {
+    let list = Types::List(vec![Types::Integer(1), Types::String(String::from("S1"))]);
+    let map = Types::Map(BTreeMap::from([
+        (String::from("a"), Types::Integer(1)),
+        (String::from("b"), list.clone()),
+    ]));
+
+    assert_eq!(list, list.clone());
+    assert_ne!(list, Types::List(vec![Types::Integer(1)]));
+    assert_eq!(map, map.clone());
+    assert_ne!(map, list);
+    assert_eq!("[1, \"S1\"]", format!("{}", list));
+    assert_eq!("{\"a\": 1, \"b\": [1, \"S1\"]}", format!("{}", map));
+
+    assert_eq!(QueryTypes::AnyList, list);
+    assert_eq!(QueryTypes::from(&list), list);
+    assert_eq!(
+        QueryTypes::ListContains(Box::new(QueryTypes::AnyString)),
+        list
+    );
+    assert_ne!(
+        QueryTypes::ListContains(Box::new(QueryTypes::AnyFloat)),
+        list
+    );
+    assert_eq!(
+        QueryTypes::ListLength(Bound::Included(2), Bound::Unbounded),
+        list
+    );
+    assert_ne!(
+        QueryTypes::ListLength(Bound::Unbounded, Bound::Excluded(2)),
+        list
+    );
+    assert_ne!(
+        QueryTypes::ListLength(Bound::Unbounded, Bound::Unbounded),
+        map
+    );
+
+    assert_eq!(QueryTypes::AnyMap, map);
+    assert_eq!(QueryTypes::from(&map), map);
+    assert_eq!(
+        QueryTypes::MapEntry(String::from("a"), Box::new(QueryTypes::ExactInteger(1))),
+        map
+    );
+    assert_ne!(
+        QueryTypes::MapEntry(String::from("a"), Box::new(QueryTypes::ExactInteger(2))),
+        map
+    );
+    assert_ne!(
+        QueryTypes::MapEntry(String::from("c"), Box::new(QueryTypes::Any)),
+        map
+    );
+    assert_eq!(
+        QueryTypes::MapLength(Bound::Included(2), Bound::Included(2)),
+        map
+    );
+}
+
+#[test]
+fn test_time_compare() {
+    let timestamp = Types::Timestamp(Timestamp::from_unix(1_614_601_800, 0));
+    let duration = Types::Duration(Duration::from_millis(1_500));
+
+    assert_eq!(
+        timestamp,
+        Types::Timestamp(Timestamp::from_unix(1_614_601_800, 0))
+    );
+    assert_ne!(
+        timestamp,
+        Types::Timestamp(Timestamp::from_unix(1_614_601_800, 1))
+    );
+    assert_ne!(timestamp, Types::Integer(1_614_601_800));
+    assert_eq!("2021-03-01T12:30:00Z", format!("{}", timestamp));
+    assert_eq!(duration, Types::Duration(Duration::from_millis(1_500)));
+    assert_eq!("PT1.5S", format!("{}", duration));
+    assert_eq!(
+        "PT60S",
+        format!("{}", Types::Duration(Duration::from_secs(60)))
+    );
+
+    assert_eq!(QueryTypes::AnyTimestamp, timestamp);
+    assert_eq!(QueryTypes::from(&timestamp), timestamp);
+    assert_eq!(
+        QueryTypes::TimestampRange(
+            Bound::Included(Timestamp::from_unix(1_614_601_800, 0)),
+            Bound::Unbounded
+        ),
+        timestamp
+    );
+    assert_ne!(
+        QueryTypes::TimestampRange(
+            Bound::Unbounded,
+            Bound::Excluded(Timestamp::from_unix(1_614_601_800, 0))
+        ),
+        timestamp
+    );
+
+    assert_eq!(QueryTypes::AnyDuration, duration);
+    assert_ne!(QueryTypes::AnyDuration, timestamp);
+    assert_eq!(
+        QueryTypes::DurationRange(
+            Bound::Included(Duration::from_secs(1)),
+            Bound::Excluded(Duration::from_secs(2))
+        ),
+        duration
+    );
+    assert_ne!(
+        QueryTypes::DurationRange(Bound::Included(Duration::from_secs(2)), Bound::Unbounded),
+        duration
+    );
+}
+
+#[test]
+fn test_null_compare() {
+    let null = Types::Null;
+    let integer = Types::Integer(1);
+
+    assert_eq!(null, Types::Null);
+    assert_ne!(null, integer);
+    assert_eq!("null", format!("{}", null));
+
+    assert_eq!(QueryTypes::Any, null);
+    assert_eq!(QueryTypes::ExactNull, null);
+    assert_ne!(QueryTypes::ExactNull, integer);
+    assert_ne!(QueryTypes::AnyInteger, null);
+    assert_eq!(QueryTypes::from(&null), null);
+
+    let optional_integer = QueryTypes::Optional(Box::new(QueryTypes::AnyInteger));
+    assert_eq!(optional_integer, null);
+    assert_eq!(optional_integer, integer);
+    assert_ne!(optional_integer, Types::Float(1.0));
+}
+
+#[test]
+fn test_wide_numeric_compare() {
+    let unsigned = Types::UnsignedInteger(u64::MAX);
+    let integer_128 = Types::Integer128(i128::MIN);
+    let decimal = Types::Decimal(Decimal::new(1250, 2));
+
+    assert_eq!(unsigned, Types::UnsignedInteger(u64::MAX));
+    assert_eq!(integer_128, Types::Integer128(i128::MIN));
+    assert_eq!(decimal, Types::Decimal(Decimal::new(12500, 3)));
+    assert_ne!(decimal, Types::Decimal(Decimal::new(1251, 2)));
+    assert_ne!(Types::UnsignedInteger(1), Types::Integer(1));
+    assert_ne!(Types::Integer128(1), Types::Integer(1));
+    assert_eq!("18446744073709551615u64", format!("{}", unsigned));
+    assert_eq!("-5i128", format!("{}", Types::Integer128(-5)));
+    assert_eq!("12.50d", format!("{}", decimal));
+
+    assert_eq!(QueryTypes::AnyUnsignedInteger, unsigned);
+    assert_eq!(QueryTypes::AnyInteger128, integer_128);
+    assert_eq!(QueryTypes::AnyDecimal, decimal);
+    assert_eq!(QueryTypes::ExactDecimal(Decimal::new(125, 1)), decimal);
+    assert_ne!(QueryTypes::ExactInteger(1), Types::UnsignedInteger(1));
+    assert_ne!(QueryTypes::AnyInteger, Types::Integer128(1));
+    assert_ne!(QueryTypes::AnyFloat, decimal);
+
+    assert_eq!(
+        QueryTypes::UnsignedIntegerRange(Bound::Included(u64::MAX), Bound::Unbounded),
+        unsigned
+    );
+    assert_eq!(
+        QueryTypes::Integer128Range(Bound::Unbounded, Bound::Excluded(0)),
+        integer_128
+    );
+    assert_eq!(
+        QueryTypes::DecimalRange(
+            Bound::Included(Decimal::new(10, 0)),
+            Bound::Excluded(Decimal::new(20, 0))
+        ),
+        decimal
+    );
+    assert_ne!(
+        QueryTypes::DecimalRange(Bound::Excluded(Decimal::new(1250, 2)), Bound::Unbounded),
+        decimal
+    );
+}
+
+#[test]
+fn test_range_compare() {
+    let integer = Types::Integer(5);
+    let float = Types::Float(0.5);
+    let string = Types::String(String::from("job/email"));
+
+    assert_eq!(
+        QueryTypes::IntegerRange(Bound::Included(5), Bound::Included(10)),
+        integer
+    );
+    assert_ne!(
+        QueryTypes::IntegerRange(Bound::Excluded(5), Bound::Included(10)),
+        integer
+    );
+    assert_ne!(
+        QueryTypes::IntegerRange(Bound::Unbounded, Bound::Unbounded),
+        float
+    );
+
+    assert_eq!(
+        QueryTypes::FloatRange(Bound::Unbounded, Bound::Excluded(1.0)),
+        float
+    );
+    assert_ne!(
+        QueryTypes::FloatRange(Bound::Included(0.6), Bound::Unbounded),
+        float
+    );
+    assert_ne!(
+        QueryTypes::FloatRange(Bound::Unbounded, Bound::Unbounded),
+        Types::Float(f64::NAN)
+    );
+
+    assert_eq!(
+        QueryTypes::StringRange(
+            Bound::Included(String::from("job/")),
+            Bound::Excluded(String::from("job0"))
+        ),
+        string
+    );
+    assert_ne!(
+        QueryTypes::StringRange(Bound::Included(String::from("k")), Bound::Unbounded),
+        string
+    );
+}
+
+#[test]
+fn test_string_pattern_compare() {
+    let string = Types::String(String::from("sensor/room1/temp"));
+
+    assert_eq!(
+        QueryTypes::StringPrefix(String::from("sensor/room1/")),
+        string
+    );
+    assert_ne!(
+        QueryTypes::StringPrefix(String::from("sensor/room2/")),
+        string
+    );
+    assert_eq!(QueryTypes::StringSuffix(String::from("/temp")), string);
+    assert_ne!(QueryTypes::StringSuffix(String::from("/humidity")), string);
+    assert_eq!(QueryTypes::StringContains(String::from("room1")), string);
+    assert_ne!(QueryTypes::StringContains(String::from("room2")), string);
+    assert_ne!(
+        QueryTypes::StringPrefix(String::new()),
+        Types::Bytes(b"sensor".to_vec())
+    );
+
+    assert_eq!(
+        QueryTypes::StringGlob(String::from("sensor/*/temp")),
+        string
+    );
+    assert_eq!(
+        QueryTypes::StringGlob(String::from("sensor/room?/*")),
+        string
+    );
+    assert_eq!(QueryTypes::StringGlob(String::from("*")), string);
+    assert_eq!(QueryTypes::StringGlob(String::from("**temp")), string);
+    assert_ne!(
+        QueryTypes::StringGlob(String::from("sensor/*/humidity")),
+        string
+    );
+    assert_ne!(QueryTypes::StringGlob(String::from("sensor/room?")), string);
+    assert_ne!(QueryTypes::StringGlob(String::from("")), string);
+    assert_eq!(
+        QueryTypes::StringGlob(String::from("")),
+        Types::String(String::new())
+    );
+}
+
+#[test]
+fn test_float_compare() {
+    let computed = Types::Float(0.1 + 0.2);
+
+    assert_ne!(QueryTypes::ExactFloat(0.3), computed);
+    let approx = |value, abs_eps, rel_eps| QueryTypes::FloatApprox {
+        value,
+        abs_eps,
+        rel_eps,
+    };
+    assert_eq!(approx(0.3, 1e-9, 0.0), computed);
+    assert_eq!(approx(0.3, 0.0, 1e-9), computed);
+    assert_ne!(approx(0.3, 0.0, 0.0), computed);
+    assert_eq!(approx(1e6, 0.0, 1e-6), Types::Float(1e6 + 0.5));
+    assert_ne!(approx(1e6, 0.1, 0.0), Types::Float(1e6 + 0.5));
+    assert_eq!(approx(f64::INFINITY, 0.0, 0.0), Types::Float(f64::INFINITY));
+    assert_ne!(approx(f64::NAN, 1.0, 1.0), Types::Float(f64::NAN));
+    assert_ne!(approx(0.0, f64::INFINITY, 0.0), Types::Float(f64::NAN));
+    assert_ne!(approx(0.3, 1.0, 1.0), Types::Integer(0));
+    assert_ne!(approx(1.0, 0.0, 1e-9), Types::Float(f64::INFINITY));
+    assert_ne!(approx(1.0, 0.0, 1e-9), Types::Float(f64::NEG_INFINITY));
+    assert_eq!(
+        approx(f64::INFINITY, 0.0, 1e-9),
+        Types::Float(f64::INFINITY)
+    );
+    assert_ne!(
+        approx(f64::INFINITY, 0.0, 1e-9),
+        Types::Float(f64::NEG_INFINITY)
+    );
+    assert_ne!(approx(1.0, f64::INFINITY, 0.0), Types::Float(f64::INFINITY));
+
+    assert_eq!(QueryTypes::FloatNaN, Types::Float(f64::NAN));
+    assert_ne!(QueryTypes::FloatNaN, Types::Float(0.0));
+
+    let zero = std::hint::black_box(0.0);
+    let computed_nan = zero / zero;
+    assert_eq!(QueryTypes::ExactFloat(f64::NAN), Types::Float(f64::NAN));
+    assert_eq!(QueryTypes::ExactFloat(f64::NAN), Types::Float(computed_nan));
+    assert_eq!(QueryTypes::ExactFloat(f64::NAN), Types::Float(-f64::NAN));
+    assert_ne!(QueryTypes::ExactFloat(f64::NAN), Types::Float(0.0));
+    assert_eq!(QueryTypes::ExactFloat(0.0), Types::Float(-zero));
+    assert!(Types::Float(f64::NAN).satisfy(&Types::Float(-f64::NAN)));
+    assert!(Types::Float(0.0).satisfy(&Types::Float(-0.0)));
+}
+
+#[test]
+fn test_combinator_compare() {
+    let channel = QueryTypes::OneOf(vec![
+        QueryTypes::ExactString(String::from("email")),
+        QueryTypes::ExactString(String::from("sms")),
+    ]);
+    assert_eq!(channel, Types::String(String::from("email")));
+    assert_eq!(channel, Types::String(String::from("sms")));
+    assert_ne!(channel, Types::String(String::from("fax")));
+    assert_ne!(QueryTypes::OneOf(Vec::new()), Types::Null);
+
+    let not_channel = QueryTypes::Not(Box::new(channel));
+    assert_ne!(not_channel, Types::String(String::from("email")));
+    assert_eq!(not_channel, Types::String(String::from("fax")));
+    assert_eq!(not_channel, Types::Integer(1));
+    assert_ne!(
+        QueryTypes::Not(Box::new(QueryTypes::Any)),
+        Types::Integer(1)
+    );
+}
+
+#[test]
+fn test_query_deterministic() {
+    let variable = || QueryTypes::Variable(String::from("x"));
+
+    let list_contains = QueryTypes::ListContains(Box::new(QueryTypes::AnyInteger));
+    assert!(list_contains.is_deterministic());
+    assert!(
+        QueryTypes::OneOf(vec![QueryTypes::AnyInteger, QueryTypes::AnyFloat]).is_deterministic()
+    );
+    assert!(!QueryTypes::ListContains(Box::new(variable())).is_deterministic());
+    assert!(!QueryTypes::OneOf(vec![QueryTypes::AnyInteger, variable()]).is_deterministic());
+    assert!(
+        QueryTypes::ListContains(Box::new(QueryTypes::Not(Box::new(variable()))))
+            .is_deterministic()
+    );
+    assert!(
+        !QueryTypes::Optional(Box::new(QueryTypes::ListContains(Box::new(
+            QueryTypes::ExactTuple(QueryTuple::builder().variable("x").build())
+        ))))
+        .is_deterministic()
+    );
+
+    let list: Vec<Types> = (0..1_000).map(Types::Integer).collect();
+    let tuple = Tuple::builder().list(&list).list(&list).string("y").build();
+    let query_tuple = QueryTuple::builder()
+        .list_contains(QueryTypes::AnyInteger)
+        .list_contains(QueryTypes::AnyInteger)
+        .string("x")
+        .build();
+    assert!(query_tuple.bind(&tuple).is_none());
+}
@@ -1,26 +1,53 @@
+use crate::lease::LeaseId;
 use crate::query_tuple::QueryTuple;
 use crate::result::Result;
 use crate::store::Store;
 use crate::tuple::Tuple;
+use std::time::{Duration, Instant};
 
 /// VecStore is a simple tuple store using [Vec] for internal storage.
 ///
-/// Tuples are stored as [Option]<[Tuple]> types and replaced by [None] when taken from the [Vec]. This reduces the need to constantly shift elements in the [Vec] as tuples are taken.
+/// Tuples are stored as [Option] types and replaced by [None] when taken from the [Vec]. This reduces the need to constantly shift elements in the [Vec] as tuples are taken.
 ///
 /// When the margin of [Some] elements is less than `compact_margin` of the total number of elements stored, the underlying [Vec] is compacted using [Vec::retain] and all the
 /// [None] elements are removed. The default value of `compact_margin` is [DEFAULT_COMPACT_MARGIN] and can be overwritten on initialisation.
+///
+/// Tuples written with a lease are ignored once the lease expires, and are removed when [Store::purge_expired] is called.
 
 #[derive(Clone)]
 pub struct VecStore {
-    inner: Vec<Option<Tuple>>,
+    inner: Vec<Option<Entry>>,
     tuple_count: usize,
+    lease_count: usize,
+    next_lease_id: u64,
     compact_margin: f64,
 }
 
 pub const DEFAULT_COMPACT_MARGIN: f64 = 0.9;
 
+#[derive(Clone)]
+struct Entry {
+    tuple: Tuple,
+    lease: Option<Lease>,
+}
+
+#[derive(Clone, Copy)]
+struct Lease {
+    id: LeaseId,
+    /// [None] if the lease duration is too long to represent as an [Instant], so that the
+    /// lease never expires.
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        matches!(self.lease, Some(Lease { expires_at: Some(expires_at), .. }) if expires_at <= now)
+    }
+}
+
 impl VecStore {
-    /// The number of [Some] enum values containing [Tuple] structs currently in the [Vec].
+    /// The number of [Some] enum values containing [Tuple] structs currently in the [Vec],
+    /// including tuples with an expired lease that have not been removed yet.
     pub fn tuple_count(&self) -> usize {
         self.tuple_count
     }
@@ -44,15 +71,119 @@ impl VecStore {
         VecStoreBuilder::default()
     }
 
+    /// The tuples that have not expired at `now`, with their index in the [Vec].
+    fn live_tuples(&self, now: Instant) -> impl Iterator<Item = (usize, &Tuple)> {
+        self.inner
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, vec_element)| match vec_element {
+                Some(entry) if !entry.is_expired(now) => Some((index, &entry.tuple)),
+                _ => None,
+            })
+    }
+
+    /// Finds a distinct index for each query tuple, returned in the order of the query tuples,
+    /// or [None] if there is no such assignment. Query tuples are assigned one at a time along
+    /// augmenting paths, which move earlier query tuples to other tuples they match (bipartite
+    /// matching), so the search is polynomial in the number of query tuples and tuples.
+    fn indices_of(&self, query_tuples: &[QueryTuple], now: Instant) -> Option<Vec<usize>> {
+        let candidates: Vec<Vec<usize>> = query_tuples
+            .iter()
+            .map(|query_tuple| {
+                self.live_tuples(now)
+                    .filter(|(_, tuple)| query_tuple == *tuple)
+                    .map(|(index, _)| index)
+                    .collect()
+            })
+            .collect();
+        if candidates.iter().any(Vec::is_empty) {
+            return None;
+        }
+
+        let mut claimed_by = vec![None; self.inner.len()];
+        for query_index in 0..query_tuples.len() {
+            let mut visited = vec![false; self.inner.len()];
+            if !augment(&candidates, query_index, &mut claimed_by, &mut visited) {
+                return None;
+            }
+        }
+
+        let mut indices = vec![0; query_tuples.len()];
+        for (index, claimant) in claimed_by.into_iter().enumerate() {
+            if let Some(query_index) = claimant {
+                indices[query_index] = index;
+            }
+        }
+        Some(indices)
+    }
+
     fn index_of(&self, query_tuple: &QueryTuple) -> Option<usize> {
+        self.live_tuples(Instant::now())
+            .find(|(_, tuple)| query_tuple == *tuple)
+            .map(|(index, _)| index)
+    }
+
+    fn index_of_lease(&self, lease_id: LeaseId) -> Option<usize> {
         self.inner.iter().position(|vec_element| {
-            if let Some(tuple) = vec_element {
-                query_tuple == tuple
-            } else {
-                false
-            }
+            matches!(vec_element, Some(Entry { lease: Some(lease), .. }) if lease.id == lease_id)
         })
     }
+
+    fn push(&mut self, tuple: Tuple, lease: Option<Lease>) {
+        self.inner.push(Some(Entry { tuple, lease }));
+        self.tuple_count += 1;
+        if lease.is_some() {
+            self.lease_count += 1;
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> Option<Entry> {
+        let entry = self.inner[index].take()?;
+        self.tuple_count -= 1;
+        if entry.lease.is_some() {
+            self.lease_count -= 1;
+        }
+        Some(entry)
+    }
+
+    fn remove_expired(&mut self, now: Instant) -> Vec<Tuple> {
+        if self.lease_count == 0 {
+            return Vec::new();
+        }
+        let mut removed = Vec::new();
+        for index in 0..self.inner.len() {
+            if matches!(&self.inner[index], Some(entry) if entry.is_expired(now)) {
+                removed.extend(self.remove(index).map(|entry| entry.tuple));
+            }
+        }
+        removed
+    }
+}
+
+/// Claims one of the `candidates` of the query tuple at `query_index`, taking a tuple claimed by
+/// another query tuple if that one can claim a different tuple instead. `visited` marks the
+/// tuples already tried while searching for the current augmenting path.
+fn augment(
+    candidates: &[Vec<usize>],
+    query_index: usize,
+    claimed_by: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for &index in &candidates[query_index] {
+        if visited[index] {
+            continue;
+        }
+        visited[index] = true;
+        let claimable = match claimed_by[index] {
+            Some(claimant) => augment(candidates, claimant, claimed_by, visited),
+            None => true,
+        };
+        if claimable {
+            claimed_by[index] = Some(query_index);
+            return true;
+        }
+    }
+    false
 }
 
 impl Default for VecStore {
@@ -60,6 +191,8 @@ impl Default for VecStore {
         Self {
             inner: Vec::new(),
             tuple_count: 0,
+            lease_count: 0,
+            next_lease_id: 0,
             compact_margin: DEFAULT_COMPACT_MARGIN,
         }
     }
@@ -67,33 +200,116 @@ impl Default for VecStore {
 
 impl Store for VecStore {
     fn size(&self) -> Result<usize> {
-        Ok(self.tuple_count)
+        if self.lease_count == 0 {
+            Ok(self.tuple_count)
+        } else {
+            Ok(self.live_tuples(Instant::now()).count())
+        }
     }
 
-    fn read(&self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
+    fn read_now(&self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
         match self.index_of(query_tuple) {
-            Some(index) => Ok(self.inner[index].clone()),
+            Some(index) => Ok(self.inner[index].as_ref().map(|entry| entry.tuple.clone())),
             None => Ok(None),
         }
     }
 
     fn write(&mut self, tuple: &Tuple) -> Result<()> {
         self.compact();
-        self.inner.push(Some(tuple.clone()));
-        self.tuple_count += 1;
+        self.push(tuple.clone(), None);
         Ok(())
     }
 
-    fn take(&mut self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
+    fn write_all<I>(&mut self, tuples: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Tuple>,
+    {
+        self.compact();
+        for tuple in tuples {
+            self.push(tuple, None);
+        }
+        Ok(())
+    }
+
+    fn take_now(&mut self, query_tuple: &QueryTuple) -> Result<Option<Tuple>> {
         match self.index_of(query_tuple) {
-            Some(index) => {
-                let tuple = self.inner[index].take();
-                self.tuple_count -= 1;
-                Ok(tuple)
-            }
+            Some(index) => Ok(self.remove(index).map(|entry| entry.tuple)),
             None => Ok(None),
         }
     }
+
+    fn take_many_now(&mut self, query_tuples: &[QueryTuple]) -> Result<Option<Vec<Tuple>>> {
+        match self.indices_of(query_tuples, Instant::now()) {
+            Some(indices) => Ok(indices
+                .into_iter()
+                .map(|index| self.remove(index).map(|entry| entry.tuple))
+                .collect()),
+            None => Ok(None),
+        }
+    }
+
+    fn read_all(&self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>> {
+        Ok(self
+            .live_tuples(Instant::now())
+            .filter(|(_, tuple)| query_tuple == *tuple)
+            .map(|(_, tuple)| tuple.clone())
+            .collect())
+    }
+
+    fn take_all(&mut self, query_tuple: &QueryTuple) -> Result<Vec<Tuple>> {
+        let indices: Vec<usize> = self
+            .live_tuples(Instant::now())
+            .filter(|(_, tuple)| query_tuple == *tuple)
+            .map(|(index, _)| index)
+            .collect();
+        Ok(indices
+            .into_iter()
+            .filter_map(|index| self.remove(index).map(|entry| entry.tuple))
+            .collect())
+    }
+
+    fn write_with_lease(&mut self, tuple: &Tuple, duration: Duration) -> Result<LeaseId> {
+        let id = LeaseId::new(self.next_lease_id);
+        self.next_lease_id += 1;
+        self.compact();
+        self.push(
+            tuple.clone(),
+            Some(Lease {
+                id,
+                expires_at: Instant::now().checked_add(duration),
+            }),
+        );
+        Ok(id)
+    }
+
+    fn renew_lease(&mut self, lease_id: LeaseId, duration: Duration) -> Result<bool> {
+        let now = Instant::now();
+        let lease = self
+            .index_of_lease(lease_id)
+            .and_then(|index| self.inner[index].as_mut())
+            .filter(|entry| !entry.is_expired(now))
+            .and_then(|entry| entry.lease.as_mut());
+        match lease {
+            Some(lease) => {
+                lease.expires_at = now.checked_add(duration);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn cancel_lease(&mut self, lease_id: LeaseId) -> Result<Option<Tuple>> {
+        let now = Instant::now();
+        Ok(self
+            .index_of_lease(lease_id)
+            .and_then(|index| self.remove(index))
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| entry.tuple))
+    }
+
+    fn purge_expired(&mut self) -> Result<Vec<Tuple>> {
+        Ok(self.remove_expired(Instant::now()))
+    }
 }
 
 pub struct VecStoreBuilder {
@@ -133,7 +349,7 @@ fn test_vec_store() -> Result<()> {
     assert_eq!(2, tuple_store.size()?);
     assert_eq!(2, tuple_store.tuple_count());
 
-    match tuple_store.read(&QueryTuple::builder().integer(2).build())? {
+    match tuple_store.read_now(&QueryTuple::builder().integer(2).build())? {
         Some(_tuple) => (),
         None => panic!("No tuple found"),
     }
@@ -141,7 +357,7 @@ fn test_vec_store() -> Result<()> {
     assert_eq!(2, tuple_store.size()?);
     assert_eq!(2, tuple_store.tuple_count());
 
-    match tuple_store.take(&QueryTuple::builder().integer(5).build())? {
+    match tuple_store.take_now(&QueryTuple::builder().integer(5).build())? {
         Some(_tuple) => (),
         None => panic!("No tuple found"),
     }
@@ -149,7 +365,7 @@ fn test_vec_store() -> Result<()> {
     assert_eq!(1, tuple_store.size()?);
     assert_eq!(1, tuple_store.tuple_count());
 
-    match tuple_store.take(&QueryTuple::builder().any_integer().build())? {
+    match tuple_store.take_now(&QueryTuple::builder().any_integer().build())? {
         Some(_tuple) => (),
         None => panic!("No tuple found"),
     }
@@ -157,12 +373,199 @@ fn test_vec_store() -> Result<()> {
     assert_eq!(0, tuple_store.size()?);
     assert_eq!(0, tuple_store.tuple_count());
 
-    match tuple_store.take(&QueryTuple::builder().any_integer().build())? {
-        Some(_tuple) => panic!("Tuple found"),
-        None => (),
+    if let Some(_tuple) = tuple_store.take_now(&QueryTuple::builder().any_integer().build())? {
+        panic!("Tuple found")
     }
     assert_eq!(0, tuple_store.size()?);
     assert_eq!(0, tuple_store.tuple_count());
 
     Ok(())
 }
+
+#[test]
+fn test_vec_store_all() -> Result<()> {
+    let mut tuple_store = VecStore::default();
+
+    for i in 0..5 {
+        tuple_store.write(&Tuple::builder().integer(i).build())?;
+        tuple_store.write(&Tuple::builder().string("S").build())?;
+    }
+
+    let query_tuple = QueryTuple::builder().any_integer().build();
+    assert_eq!(5, tuple_store.read_all(&query_tuple)?.len());
+    assert_eq!(10, tuple_store.size()?);
+
+    let tuples = tuple_store.take_all(&query_tuple)?;
+    assert_eq!(5, tuples.len());
+    for (i, tuple) in tuples.iter().enumerate() {
+        assert_eq!(QueryTuple::builder().integer(i as i64).build(), *tuple);
+    }
+    assert_eq!(5, tuple_store.size()?);
+    assert!(tuple_store.take_all(&query_tuple)?.is_empty());
+    assert_eq!(
+        5,
+        tuple_store
+            .read_all(&QueryTuple::builder().any().build())?
+            .len()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_vec_store_take_many() -> Result<()> {
+    let mut tuple_store = VecStore::default();
+
+    tuple_store.write(&Tuple::builder().integer(1).build())?;
+    tuple_store.write(&Tuple::builder().string("S").build())?;
+
+    let query_tuples = [
+        QueryTuple::builder().any().build(),
+        QueryTuple::builder().any_integer().build(),
+    ];
+    let unmatched_query_tuples = [
+        QueryTuple::builder().any_integer().build(),
+        QueryTuple::builder().any_integer().build(),
+    ];
+
+    assert!(tuple_store
+        .take_many_now(&unmatched_query_tuples)?
+        .is_none());
+    assert_eq!(2, tuple_store.size()?);
+
+    match tuple_store.take_many_now(&query_tuples)? {
+        Some(tuples) => {
+            assert_eq!(QueryTuple::builder().any_string().build(), tuples[0]);
+            assert_eq!(query_tuples[1], tuples[1]);
+        }
+        None => panic!("No tuples found"),
+    }
+    assert_eq!(0, tuple_store.size()?);
+
+    for i in 0..1_000 {
+        tuple_store.write(&Tuple::builder().integer(i).build())?;
+    }
+    let last_unmatched_query_tuples = [
+        QueryTuple::builder().any().build(),
+        QueryTuple::builder().any().build(),
+        QueryTuple::builder().string("x").build(),
+    ];
+    assert!(tuple_store
+        .take_many_now(&last_unmatched_query_tuples)?
+        .is_none());
+
+    tuple_store.write(&Tuple::builder().string("x").build())?;
+    tuple_store.write(&Tuple::builder().string("y").build())?;
+    let too_many_query_tuples = [
+        QueryTuple::builder().any_string().build(),
+        QueryTuple::builder().any_string().build(),
+        QueryTuple::builder().any_string().build(),
+    ];
+    assert!(tuple_store.take_many_now(&too_many_query_tuples)?.is_none());
+    assert_eq!(1_002, tuple_store.size()?);
+
+    let reassigned_query_tuples = [
+        QueryTuple::builder().any_string().build(),
+        QueryTuple::builder().string("x").build(),
+    ];
+    match tuple_store.take_many_now(&reassigned_query_tuples)? {
+        Some(tuples) => {
+            assert_eq!(Tuple::builder().string("y").build(), tuples[0]);
+            assert_eq!(reassigned_query_tuples[1], tuples[1]);
+        }
+        None => panic!("No tuples found"),
+    }
+    assert_eq!(1_000, tuple_store.size()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_vec_store_lease() -> Result<()> {
+    use std::thread;
+
+    let mut tuple_store = VecStore::default();
+    let query_tuple = QueryTuple::builder().any_integer().build();
+
+    let short_lease = tuple_store.write_with_lease(
+        &Tuple::builder().integer(1).build(),
+        Duration::from_millis(20),
+    )?;
+    let renewed_lease = tuple_store.write_with_lease(
+        &Tuple::builder().integer(2).build(),
+        Duration::from_millis(20),
+    )?;
+    let cancelled_lease = tuple_store.write_with_lease(
+        &Tuple::builder().string("S").build(),
+        Duration::from_secs(60),
+    )?;
+    assert_eq!(3, tuple_store.size()?);
+
+    assert!(tuple_store.renew_lease(renewed_lease, Duration::from_secs(60))?);
+    match tuple_store.cancel_lease(cancelled_lease)? {
+        Some(tuple) => assert_eq!(QueryTuple::builder().string("S").build(), tuple),
+        None => panic!("No tuple found"),
+    }
+    assert!(tuple_store.cancel_lease(cancelled_lease)?.is_none());
+
+    thread::sleep(Duration::from_millis(40));
+
+    assert_eq!(1, tuple_store.size()?);
+    assert_eq!(2, tuple_store.tuple_count());
+    assert!(!tuple_store.renew_lease(short_lease, Duration::from_secs(60))?);
+    assert_eq!(1, tuple_store.read_all(&query_tuple)?.len());
+
+    assert_eq!(1, tuple_store.purge_expired()?.len());
+    assert_eq!(1, tuple_store.tuple_count());
+    match tuple_store.take_now(&query_tuple)? {
+        Some(tuple) => assert_eq!(QueryTuple::builder().integer(2).build(), tuple),
+        None => panic!("No tuple found"),
+    }
+    assert!(!tuple_store.renew_lease(renewed_lease, Duration::from_secs(60))?);
+
+    let endless_lease =
+        tuple_store.write_with_lease(&Tuple::builder().integer(3).build(), Duration::MAX)?;
+    assert!(tuple_store.renew_lease(endless_lease, Duration::MAX)?);
+    assert!(tuple_store.purge_expired()?.is_empty());
+    assert_eq!(1, tuple_store.size()?);
+    assert!(tuple_store.cancel_lease(endless_lease)?.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_vec_store_compact_keeps_expired() -> Result<()> {
+    use std::thread;
+
+    let mut tuple_store = VecStore::builder().compact_margin(1.0).build();
+    let heartbeat = Tuple::builder().string("heartbeat").build();
+    tuple_store.write_with_lease(&heartbeat, Duration::from_millis(10))?;
+    tuple_store.write(&Tuple::builder().integer(1).build())?;
+    tuple_store.take_now(&QueryTuple::builder().integer(1).build())?;
+    thread::sleep(Duration::from_millis(20));
+
+    tuple_store.write(&Tuple::builder().integer(2).build())?;
+    assert_eq!(2, tuple_store.tuple_count());
+    let expired = tuple_store.purge_expired()?;
+    assert_eq!(1, expired.len());
+    assert_eq!(heartbeat, expired[0]);
+    assert_eq!(1, tuple_store.tuple_count());
+
+    Ok(())
+}
+
+#[test]
+fn test_vec_store_write_all() -> Result<()> {
+    let mut tuple_store = VecStore::default();
+
+    tuple_store.write_all((0..10).map(|i| Tuple::builder().integer(i).build()))?;
+    assert_eq!(10, tuple_store.size()?);
+    assert_eq!(10, tuple_store.tuple_count());
+
+    tuple_store.take_all(&QueryTuple::builder().any_integer().build())?;
+    tuple_store.write_all(vec![Tuple::builder().string("S").build()])?;
+    assert_eq!(1, tuple_store.size()?);
+    assert_eq!(1, tuple_store.inner.len());
+
+    Ok(())
+}